@@ -124,13 +124,13 @@ export class MinesweeperClientApplication {
      *  - Second array - number of `rows` (y axis)
      */
     private generateField(field: Array<Array<WasmCell>>): void {
-        field.forEach((rows, col_index) => {
-            rows.forEach((cell, row_index) => {
+        field.forEach(rows => {
+            rows.forEach(cell => {
                 const cellVisual = new CellVisual(cell.id);
                 const props = {
                     position: {
-                        x: col_index * COLUMN_SIZE,
-                        y: row_index * COLUMN_SIZE,
+                        x: cell.position.x * COLUMN_SIZE,
+                        y: cell.position.y * COLUMN_SIZE,
                     },
                     size: {
                         width: COLUMN_SIZE,
@@ -191,7 +191,11 @@ export class MinesweeperClientApplication {
                     throw new Error(`Cannot find visual by id: ${cell.id}`);
                 }
 
-                visual.setProps({status: cell.status});
+                /**
+                 * Hidden cells come without their types so the type
+                 *  has to be updated when the cell is revealed
+                 */
+                visual.setProps({status: cell.status, ctype: cell.ctype});
                 visual.render();
             });
         }
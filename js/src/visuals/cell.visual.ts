@@ -2,6 +2,12 @@ import * as PIXI from 'pixi.js';
 import {IVisual} from './visual.interface';
 import {WasmCellState, WasmCType, WasmCTypeName} from '@minesweeper/engine';
 
+/** Tint of negative mines and flags */
+const NEGATIVE_TINT = 0x6699ff;
+
+/** Tint of mines which have been hit and of wrong flags */
+const EXPLODED_TINT = 0xff6666;
+
 export interface ICellVisualProps {
     position: {
         x: number;
@@ -64,7 +70,10 @@ export class CellVisual implements IVisual<ICellVisualProps> {
             this.graphics.interactive = false;
             this.graphics.buttonMode = false;
 
-            if (this.props.ctype.name === WasmCTypeName.Mine) {
+            if (
+                this.props.ctype.name === WasmCTypeName.Mine ||
+                this.props.ctype.name === WasmCTypeName.NegativeMine
+            ) {
                 this.sprite.texture = PIXI.Texture.from('bomb');
             } else {
                 if (this.props.ctype.value > 0) {
@@ -80,13 +89,43 @@ export class CellVisual implements IVisual<ICellVisualProps> {
             this.graphics.buttonMode = true;
 
             this.sprite.texture = PIXI.Texture.from('empty_not_selected');
-        } else if (this.props.status === WasmCellState.Flagged) {
+        } else if (this.props.status === WasmCellState.Exploded) {
+            this.graphics.interactive = false;
+            this.graphics.buttonMode = false;
+
+            this.sprite.texture = PIXI.Texture.from('bomb');
+        } else if (
+            this.props.status === WasmCellState.Flagged ||
+            this.props.status === WasmCellState.NegativeFlagged ||
+            this.props.status === WasmCellState.WrongFlag
+        ) {
             this.graphics.interactive = true;
             this.graphics.buttonMode = true;
 
             this.sprite.texture = PIXI.Texture.from('flagged');
         }
 
+        // Negative mines and flags look like ordinary ones but tinted
+        const isNegative =
+            this.props.status === WasmCellState.NegativeFlagged ||
+            (this.props.status === WasmCellState.Revealed &&
+                this.props.ctype.name === WasmCTypeName.NegativeMine);
+        const isExploded =
+            this.props.status === WasmCellState.Exploded ||
+            this.props.status === WasmCellState.WrongFlag;
+        this.sprite.tint = isExploded
+            ? EXPLODED_TINT
+            : isNegative
+            ? NEGATIVE_TINT
+            : 0xffffff;
+
+        // Cells outside of the board shape are not drawn at all
+        this.graphics.visible = this.props.status !== WasmCellState.Void;
+        if (this.props.status === WasmCellState.Void) {
+            this.graphics.interactive = false;
+            this.graphics.buttonMode = false;
+        }
+
         this.graphics.x = this.props.position.x;
         this.graphics.y = this.props.position.y;
 
@@ -0,0 +1,46 @@
+/// Predefined levels of hardness
+///
+/// Values are the same as in `js/src/difficulty_config.ts`
+///  so the terminal and the browser versions play the same boards
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    pub fn rows(self) -> usize {
+        match self {
+            Difficulty::Easy => 10,
+            Difficulty::Medium => 12,
+            Difficulty::Hard => 15,
+        }
+    }
+
+    pub fn cols(self) -> usize {
+        match self {
+            Difficulty::Easy => 7,
+            Difficulty::Medium => 9,
+            Difficulty::Hard => 10,
+        }
+    }
+
+    pub fn bombs(self) -> u16 {
+        match self {
+            Difficulty::Easy => 7,
+            Difficulty::Medium => 10,
+            Difficulty::Hard => 20,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Terminal version of the Minesweeper game
+//!
+//! Plays the game on top of `minesweeper_engine::engine`
+//!  without any browser or Web Assembly glue
+//!
+//! Usage:
+//!  - `minesweeper` or `minesweeper play` - plays the game in the terminal
+//!  - `minesweeper simulate [options]` - plays games by the bot and prints statistics
+
+mod difficulty;
+mod simulate;
+mod tui;
+
+const USAGE: &str = "Usage:
+    minesweeper [play]
+    minesweeper simulate [options]
+
+Simulate options:
+    --difficulty <easy|medium|hard>   level of hardness (default: easy)
+    --games <N>                       how many games to play (default: 100)
+    --seed <S>                        seed of the first game (default: 0)
+    --strategy <random,solver>        comma separated bot strategies (default: solver)
+    --csv                             prints results as CSV instead of the table";
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+    let result = match args.first().map(String::as_str) {
+        None | Some("play") => tui::run().map_err(|error| error.to_string()),
+        Some("simulate") => simulate::run(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+
+            Ok(())
+        }
+        Some(command) => Err(format!("unknown command: {}\n\n{}", command, USAGE)),
+    };
+
+    if let Err(error) = result {
+        eprintln!("minesweeper: {}", error);
+        std::process::exit(1);
+    }
+}
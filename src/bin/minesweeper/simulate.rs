@@ -0,0 +1,297 @@
+use std::time::{Duration, Instant};
+
+use minesweeper_engine::engine::{CellId, CellState, Game, GameStatus, Solver};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::difficulty::Difficulty;
+
+/// How the bot picks the next move
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Strategy {
+    /// Reveals random hidden cells
+    Random,
+
+    /// Reveals cells which the solver proves safe
+    ///  and guesses only when nothing can be proved
+    Solver,
+}
+
+impl Strategy {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "random" => Ok(Strategy::Random),
+            "solver" => Ok(Strategy::Solver),
+            _ => Err(format!("unknown strategy: {}", value)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Strategy::Random => "random",
+            Strategy::Solver => "solver",
+        }
+    }
+}
+
+/// How the results are printed
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Format {
+    Table,
+    Csv,
+}
+
+/// Options of the `simulate` subcommand
+struct Options {
+    difficulty: Difficulty,
+    games: u64,
+    first_seed: u64,
+    strategies: Vec<Strategy>,
+    format: Format,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Self {
+            difficulty: Difficulty::Easy,
+            games: 100,
+            first_seed: 0,
+            strategies: vec![Strategy::Solver],
+            format: Format::Table,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("missing value for {}", arg))
+            };
+
+            match arg.as_str() {
+                "--difficulty" => {
+                    let name = value()?;
+
+                    options.difficulty = *Difficulty::ALL
+                        .iter()
+                        .find(|difficulty| difficulty.name() == name)
+                        .ok_or_else(|| format!("unknown difficulty: {}", name))?;
+                }
+                "--games" => {
+                    options.games = value()?
+                        .parse()
+                        .map_err(|_| "--games must be a number".to_string())?;
+                }
+                "--seed" => {
+                    options.first_seed = value()?
+                        .parse()
+                        .map_err(|_| "--seed must be a number".to_string())?;
+                }
+                "--strategy" => {
+                    options.strategies = value()?
+                        .split(',')
+                        .map(Strategy::parse)
+                        .collect::<Result<_, _>>()?;
+                }
+                "--csv" => options.format = Format::Csv,
+                _ => return Err(format!("unknown option: {}", arg)),
+            }
+        }
+
+        // Seeds of all games are added to the first one
+        if options.first_seed.checked_add(options.games).is_none() {
+            return Err(format!(
+                "--seed {} is too large to play {} games",
+                options.first_seed, options.games
+            ));
+        }
+
+        Ok(options)
+    }
+}
+
+/// Result of a single game played by the bot
+struct GameResult {
+    won: bool,
+    three_bv: u32,
+    guesses: u32,
+    duration: Duration,
+}
+
+/// Aggregated results of all games played by one strategy
+#[derive(Default)]
+struct Summary {
+    games: u64,
+    wins: u64,
+    three_bv: u64,
+    guesses: u64,
+    duration: Duration,
+}
+
+impl Summary {
+    fn add(&mut self, result: &GameResult) {
+        self.games += 1;
+        self.wins += result.won as u64;
+        self.three_bv += result.three_bv as u64;
+        self.guesses += result.guesses as u64;
+        self.duration += result.duration;
+    }
+
+    fn win_rate(&self) -> f64 {
+        self.average(self.wins) * 100.0
+    }
+
+    fn average(&self, total: u64) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            total as f64 / self.games as f64
+        }
+    }
+
+    /// Average time of a single game in milliseconds
+    fn time_per_game(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.duration.as_secs_f64() * 1000.0 / self.games as f64
+        }
+    }
+}
+
+/// Runs `simulate` subcommand with provided arguments
+pub fn run(args: &[String]) -> Result<(), String> {
+    let options = Options::parse(args)?;
+    let seeds = options.first_seed..options.first_seed + options.games;
+
+    let summaries = options
+        .strategies
+        .iter()
+        .map(|strategy| {
+            let mut summary = Summary::default();
+
+            for seed in seeds.clone() {
+                summary.add(&play(options.difficulty, *strategy, seed));
+            }
+
+            (*strategy, summary)
+        })
+        .collect::<Vec<_>>();
+
+    match options.format {
+        Format::Table => print_table(&options, &summaries),
+        Format::Csv => print_csv(&options, &summaries),
+    }
+
+    Ok(())
+}
+
+/// Plays a single game from the `seed` until it's won or lost
+fn play(difficulty: Difficulty, strategy: Strategy, seed: u64) -> GameResult {
+    let started_at = Instant::now();
+
+    let mut game = Game::with_seed(
+        difficulty.rows(),
+        difficulty.cols(),
+        difficulty.bombs(),
+        seed,
+    );
+    let three_bv = game.battlefield().three_bv();
+
+    // The bot has its own generator so every game
+    //  is reproducible by its seed
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut guesses = 0;
+
+    while game.is_played() {
+        if strategy == Strategy::Solver {
+            let deduction = Solver::new(game.battlefield()).deduce();
+
+            for mine in deduction.mines {
+                if game.battlefield().get(mine).state == CellState::Hidden {
+                    game.flag(mine);
+                }
+            }
+
+            if !deduction.safe.is_empty() {
+                for cell_id in deduction.safe {
+                    if game.battlefield().get(cell_id).state == CellState::Hidden {
+                        game.reveal(cell_id);
+                    }
+                }
+
+                continue;
+            }
+        }
+
+        let hidden_cells = game
+            .battlefield()
+            .get_all()
+            .iter()
+            .flatten()
+            .filter(|cell| cell.state == CellState::Hidden)
+            .map(|cell| cell.id)
+            .collect::<Vec<CellId>>();
+
+        match hidden_cells.choose(&mut rng) {
+            Some(cell_id) => {
+                guesses += 1;
+                game.reveal(*cell_id);
+            }
+            None => break,
+        }
+    }
+
+    GameResult {
+        won: game.state().status == GameStatus::Won,
+        three_bv,
+        guesses,
+        duration: started_at.elapsed(),
+    }
+}
+
+fn print_table(options: &Options, summaries: &[(Strategy, Summary)]) {
+    println!(
+        "difficulty: {}, seeds: {}..{}",
+        options.difficulty.name(),
+        options.first_seed,
+        options.first_seed + options.games
+    );
+    println!(
+        "{:<10} {:>8} {:>8} {:>10} {:>9} {:>14} {:>14}",
+        "strategy", "games", "wins", "win rate", "avg 3BV", "guesses/game", "ms/game"
+    );
+
+    for (strategy, summary) in summaries {
+        println!(
+            "{:<10} {:>8} {:>8} {:>9.2}% {:>9.2} {:>14.2} {:>14.3}",
+            strategy.name(),
+            summary.games,
+            summary.wins,
+            summary.win_rate(),
+            summary.average(summary.three_bv),
+            summary.average(summary.guesses),
+            summary.time_per_game()
+        );
+    }
+}
+
+fn print_csv(options: &Options, summaries: &[(Strategy, Summary)]) {
+    println!(
+        "difficulty,first_seed,strategy,games,wins,win_rate,avg_3bv,guesses_per_game,ms_per_game"
+    );
+
+    for (strategy, summary) in summaries {
+        println!(
+            "{},{},{},{},{},{:.4},{:.4},{:.4},{:.4}",
+            options.difficulty.name(),
+            options.first_seed,
+            strategy.name(),
+            summary.games,
+            summary.wins,
+            summary.win_rate() / 100.0,
+            summary.average(summary.three_bv),
+            summary.average(summary.guesses),
+            summary.time_per_game()
+        );
+    }
+}
@@ -0,0 +1,455 @@
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use minesweeper_engine::engine::{Cell, CellPosition, CellState, CellType, Game, GameStatus};
+
+use crate::difficulty::Difficulty;
+
+/// How often the screen is redrawn to keep the timer up to date
+const TICK: Duration = Duration::from_millis(200);
+
+/// Settings which were picked by the player in the menu
+#[derive(Copy, Clone)]
+struct Settings {
+    difficulty: Difficulty,
+    seed: u64,
+}
+
+/// What the player wants to do after the game screen is closed
+enum Outcome {
+    /// Go back to the menu and pick another game
+    Menu,
+
+    /// Leave the application
+    Quit,
+}
+
+/// Switches the terminal into raw mode with alternate screen
+///  and restores it back when dropped, even on panic
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> crossterm::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Runs the terminal application until the player quits
+pub fn run() -> crossterm::Result<()> {
+    let _guard = TerminalGuard::enter()?;
+    let mut stdout = io::stdout();
+
+    while let Some(settings) = select_settings(&mut stdout)? {
+        if let Outcome::Quit = play(&mut stdout, settings)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders the menu where the player picks the difficulty
+///  and optionally enters the seed of the board
+///
+/// Returns `None` if the player wants to quit
+fn select_settings(stdout: &mut Stdout) -> crossterm::Result<Option<Settings>> {
+    let mut selected = 0;
+
+    let difficulty = loop {
+        queue!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+        queue!(stdout, Print("MineSweeper"), MoveTo(0, 2))?;
+        queue!(stdout, Print("Select difficulty:"))?;
+
+        for (index, difficulty) in Difficulty::ALL.iter().enumerate() {
+            let marker = if index == selected { ">" } else { " " };
+            let line = format!(
+                "{} {:<6} {}x{}, {} bombs",
+                marker,
+                difficulty.name(),
+                difficulty.cols(),
+                difficulty.rows(),
+                difficulty.bombs()
+            );
+
+            queue!(stdout, MoveTo(2, 3 + index as u16), Print(line))?;
+        }
+
+        queue!(
+            stdout,
+            MoveTo(0, 4 + Difficulty::ALL.len() as u16),
+            Print("Up/Down to select, Enter to confirm, q to quit")
+        )?;
+        stdout.flush()?;
+
+        let key = match read_key()? {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => {
+                selected = selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                selected = (selected + 1).min(Difficulty::ALL.len() - 1);
+            }
+            KeyCode::Char(c @ '1'..='3') => {
+                selected = c as usize - '1' as usize;
+            }
+            KeyCode::Enter => break Difficulty::ALL[selected],
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+            _ => {}
+        }
+    };
+
+    let mut seed_input = String::new();
+
+    let seed = loop {
+        queue!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+        queue!(stdout, Print("MineSweeper"), MoveTo(0, 2))?;
+        queue!(
+            stdout,
+            Print(format!("Difficulty: {}", difficulty.name())),
+            MoveTo(0, 3),
+            Print(format!("Seed (empty for random): {}_", seed_input)),
+            MoveTo(0, 5),
+            Print("Enter to start, Esc to go back")
+        )?;
+        stdout.flush()?;
+
+        let key = match read_key()? {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+
+        match key {
+            KeyCode::Char(c) if c.is_ascii_digit() && seed_input.len() < 19 => {
+                seed_input.push(c);
+            }
+            KeyCode::Backspace => {
+                seed_input.pop();
+            }
+            KeyCode::Enter => {
+                break seed_input
+                    .parse::<u64>()
+                    .unwrap_or_else(|_| rand::random::<u64>());
+            }
+            KeyCode::Esc => return select_settings(stdout),
+            _ => {}
+        }
+    };
+
+    Ok(Some(Settings { difficulty, seed }))
+}
+
+/// The game screen with the battlefield and status line
+struct Screen {
+    settings: Settings,
+    game: Game,
+
+    /// Position of the cursor on the battlefield
+    cursor: CellPosition,
+
+    /// When the first cell has been revealed
+    started_at: Option<Instant>,
+
+    /// How long the game took once it has ended
+    finished_in: Option<Duration>,
+}
+
+impl Screen {
+    fn new(settings: Settings) -> Self {
+        let difficulty = settings.difficulty;
+
+        Self {
+            settings,
+            game: Game::with_seed(
+                difficulty.rows(),
+                difficulty.cols(),
+                difficulty.bombs(),
+                settings.seed,
+            ),
+            cursor: CellPosition { x: 0, y: 0 },
+            started_at: None,
+            finished_in: None,
+        }
+    }
+
+    /// Starts the same map over
+    fn restart(&mut self) {
+        self.game.restart();
+        self.started_at = None;
+        self.finished_in = None;
+    }
+
+    /// Returns the cell which is under the cursor
+    fn current_cell(&self) -> Cell {
+        self.game.battlefield().get_all()[self.cursor.x as usize][self.cursor.y as usize]
+    }
+
+    /// Moves the cursor and keeps it inside the battlefield
+    fn move_cursor(&mut self, x: i16, y: i16) {
+        let battlefield = self.game.battlefield();
+
+        self.cursor.x = (self.cursor.x + x).clamp(0, battlefield.cols() as i16 - 1);
+        self.cursor.y = (self.cursor.y + y).clamp(0, battlefield.rows() as i16 - 1);
+    }
+
+    /// Reveals the cell under the cursor
+    ///  or chords it if it's already revealed
+    ///
+    /// Flagged cells are protected from accidental reveal
+    fn reveal(&mut self) {
+        let cell = self.current_cell();
+
+        match cell.state {
+            CellState::Revealed => {
+                self.game.chord(cell.id);
+            }
+            CellState::Hidden => {
+                self.start_timer();
+                self.game.reveal(cell.id);
+            }
+            CellState::Flagged(_)
+            | CellState::NegativeFlagged(_)
+            | CellState::Exploded
+            | CellState::WrongFlag
+            | CellState::Void => {}
+        }
+
+        self.stop_timer_if_ended();
+    }
+
+    fn chord(&mut self) {
+        let cell = self.current_cell();
+
+        self.game.chord(cell.id);
+        self.stop_timer_if_ended();
+    }
+
+    fn flag(&mut self) {
+        let cell = self.current_cell();
+
+        self.game.flag(cell.id);
+        self.stop_timer_if_ended();
+    }
+
+    fn start_timer(&mut self) {
+        if self.started_at.is_none() {
+            self.started_at = Some(Instant::now());
+        }
+    }
+
+    fn stop_timer_if_ended(&mut self) {
+        if !self.game.is_played() && self.finished_in.is_none() {
+            self.finished_in = Some(self.elapsed());
+        }
+    }
+
+    /// Returns how long the game is going
+    fn elapsed(&self) -> Duration {
+        match (self.finished_in, self.started_at) {
+            (Some(finished_in), _) => finished_in,
+            (None, Some(started_at)) => started_at.elapsed(),
+            (None, None) => Duration::from_secs(0),
+        }
+    }
+
+    fn draw(&self, stdout: &mut Stdout) -> crossterm::Result<()> {
+        let battlefield = self.game.battlefield();
+        let state = self.game.state();
+
+        queue!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+        queue!(
+            stdout,
+            Print(format!(
+                "Mines left: {:<4} Time: {:03}   {} (seed {})",
+                state.flags,
+                self.elapsed().as_secs(),
+                self.settings.difficulty.name(),
+                self.settings.seed
+            ))
+        )?;
+
+        for y in 0..battlefield.rows() {
+            queue!(stdout, MoveTo(0, 2 + y as u16))?;
+
+            for x in 0..battlefield.cols() {
+                let cell = &battlefield.get_all()[x][y];
+                let is_cursor = self.cursor.x == x as i16 && self.cursor.y == y as i16;
+
+                if is_cursor {
+                    queue!(stdout, SetAttribute(Attribute::Reverse))?;
+                }
+
+                draw_cell(stdout, cell)?;
+
+                if is_cursor {
+                    queue!(stdout, SetAttribute(Attribute::Reset))?;
+                }
+            }
+        }
+
+        let message = match state.status {
+            GameStatus::Played => "",
+            GameStatus::Won => "You won! ",
+            GameStatus::Lose => "Game is over. ",
+        };
+
+        queue!(
+            stdout,
+            MoveTo(0, 3 + battlefield.rows() as u16),
+            Print(message),
+            Print("Arrows/hjkl move, Space reveal, f flag, c chord, r replay, n new game, q quit")
+        )?;
+
+        stdout.flush()
+    }
+}
+
+/// Draws a single cell which takes two columns of the terminal
+fn draw_cell(stdout: &mut Stdout, cell: &Cell) -> crossterm::Result<()> {
+    match (cell.state, cell.ctype) {
+        (CellState::Void, _) => queue!(stdout, Print("  ")),
+        (CellState::Hidden, _) => queue!(stdout, Print(" #")),
+        (CellState::Flagged(flags), _) => queue!(
+            stdout,
+            SetForegroundColor(Color::Red),
+            Print(counted_symbol(flags, 'F')),
+            ResetColor
+        ),
+        (CellState::NegativeFlagged(flags), _) => queue!(
+            stdout,
+            SetForegroundColor(Color::Blue),
+            Print(counted_symbol(flags, 'F')),
+            ResetColor
+        ),
+        (CellState::Exploded, ctype) => queue!(
+            stdout,
+            SetForegroundColor(Color::DarkRed),
+            Print(counted_symbol(ctype.mines(), 'X')),
+            ResetColor
+        ),
+        (CellState::WrongFlag, _) => queue!(
+            stdout,
+            SetForegroundColor(Color::Magenta),
+            Print(" x"),
+            ResetColor
+        ),
+        (CellState::Revealed, CellType::Mine(mines)) => queue!(
+            stdout,
+            SetForegroundColor(Color::Red),
+            Print(counted_symbol(mines, '*')),
+            ResetColor
+        ),
+        (CellState::Revealed, CellType::NegativeMine(mines)) => queue!(
+            stdout,
+            SetForegroundColor(Color::Blue),
+            Print(counted_symbol(mines, '*')),
+            ResetColor
+        ),
+        (CellState::Revealed, CellType::Empty(0)) => queue!(stdout, Print(" .")),
+        (CellState::Revealed, CellType::Empty(value)) => queue!(
+            stdout,
+            SetForegroundColor(number_color(value.unsigned_abs())),
+            Print(format!("{:>2}", value)),
+            ResetColor
+        ),
+    }
+}
+
+/// Prefixes the symbol with the count when the cell
+///  has more than one mine or flag
+fn counted_symbol(count: u8, symbol: char) -> String {
+    if count > 1 {
+        format!("{}{}", count, symbol)
+    } else {
+        format!(" {}", symbol)
+    }
+}
+
+/// Returns classic color of the number which shows
+///  how many bombs are around the cell
+fn number_color(value: u8) -> Color {
+    match value {
+        1 => Color::Blue,
+        2 => Color::Green,
+        3 => Color::Red,
+        4 => Color::DarkBlue,
+        5 => Color::DarkRed,
+        6 => Color::Cyan,
+        7 => Color::Magenta,
+        _ => Color::Grey,
+    }
+}
+
+/// Plays a single game until the player leaves the game screen
+fn play(stdout: &mut Stdout, settings: Settings) -> crossterm::Result<Outcome> {
+    let mut screen = Screen::new(settings);
+
+    loop {
+        screen.draw(stdout)?;
+
+        if !event::poll(TICK)? {
+            continue;
+        }
+
+        let key = match event::read()? {
+            Event::Key(key) => key,
+            _ => continue,
+        };
+
+        if is_interrupt(&key) {
+            return Ok(Outcome::Quit);
+        }
+
+        match key.code {
+            KeyCode::Left | KeyCode::Char('h') => screen.move_cursor(-1, 0),
+            KeyCode::Right | KeyCode::Char('l') => screen.move_cursor(1, 0),
+            KeyCode::Up | KeyCode::Char('k') => screen.move_cursor(0, -1),
+            KeyCode::Down | KeyCode::Char('j') => screen.move_cursor(0, 1),
+            KeyCode::Char(' ') | KeyCode::Enter => screen.reveal(),
+            KeyCode::Char('f') => screen.flag(),
+            KeyCode::Char('c') => screen.chord(),
+            KeyCode::Char('r') => screen.restart(),
+            KeyCode::Char('n') => return Ok(Outcome::Menu),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(Outcome::Quit),
+            _ => {}
+        }
+    }
+}
+
+/// Blocks until the player presses a key
+///
+/// Returns `None` if the player interrupts the application
+fn read_key() -> crossterm::Result<Option<KeyCode>> {
+    loop {
+        if let Event::Key(key) = event::read()? {
+            if is_interrupt(&key) {
+                return Ok(None);
+            }
+
+            return Ok(Some(key.code));
+        }
+    }
+}
+
+/// Raw mode swallows `Ctrl+C` so we have to handle it by ourselves
+fn is_interrupt(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
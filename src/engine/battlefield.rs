@@ -1,5 +1,17 @@
+use std::collections::HashSet;
+
 use crate::engine::cell::*;
+use crate::engine::config::{GameConfig, MAX_MINES_PER_CELL};
+use crate::engine::grid::Grid;
+use crate::engine::solver::Solver;
+use crate::engine::topology::Topology;
+#[cfg(test)]
+use crate::engine::{
+    mask::Mask,
+    topology::{Neighbourhood, Tiling},
+};
 use rand::prelude::*;
+use rand::rngs::StdRng;
 
 /// Battlefield map represents the field
 ///  when the first vector is a `x` axis or `cols`
@@ -7,7 +19,11 @@ use rand::prelude::*;
 type BattlefieldMap = Vec<Vec<Cell>>;
 
 /// The main map of the battle
-pub struct BattleField {
+///
+/// The battlefield may have any geometry which implements `Topology`,
+///  all built-in battlefields use `Grid`
+#[derive(Clone)]
+pub struct BattleField<T: Topology = Grid> {
     /// Current map
     map: BattlefieldMap,
 
@@ -16,15 +32,108 @@ pub struct BattleField {
     pub flags_left: u16,
 
     /// How many bombs has been placed on the map
+    ///  counting every mine of multi-mine cells
+    bombs: u16,
+
+    /// How many negative bombs has been placed on the map
+    ///  in addition to `bombs`
+    negative_bombs: u16,
+
+    /// How many mines a single cell may contain
+    max_mines_per_cell: u8,
+
+    /// How many mines the player may hit before the game is lost
+    lives: u8,
+
+    /// How many mines the player may still hit,
+    ///  the game is lost when it becomes `0`
+    pub lives_left: u8,
+
+    /// Geometry of the map which decides
+    ///  which cells exist and which are neighbours
+    topology: T,
+}
+
+/// How many mines of every kind should be placed on the map
+#[derive(Copy, Clone)]
+struct Mines {
     bombs: u16,
+    negative_bombs: u16,
+    max_mines_per_cell: u8,
+}
+
+impl Mines {
+    /// Single mines of the ordinary kind only
+    fn classic(bombs: u16) -> Self {
+        Self {
+            bombs,
+            negative_bombs: 0,
+            max_mines_per_cell: 1,
+        }
+    }
+
+    /// Returns the same mines but with at most as many mines per cell
+    ///  as keep values of cells within `i8` on provided `topology`
+    fn bounded<T: Topology>(self, topology: &T) -> Self {
+        let mut max_neighbours = 1;
+
+        for x in 0..topology.width() {
+            for y in 0..topology.height() {
+                let position = CellPosition {
+                    x: x as i16,
+                    y: y as i16,
+                };
+
+                max_neighbours = max_neighbours.max(topology.neighbours(position).len());
+            }
+        }
+
+        let limit = (i8::MAX as usize / max_neighbours).clamp(1, MAX_MINES_PER_CELL as usize);
+
+        Self {
+            max_mines_per_cell: self.max_mines_per_cell.clamp(1, limit as u8),
+            ..self
+        }
+    }
 }
 
+#[derive(Default)]
 pub struct Reveal {
     pub game_is_over: bool,
     pub cells: Vec<Cell>,
+
+    /// What happened to mines and flags if the game has been lost
+    pub loss: Option<LossReport>,
+
+    /// Mines which have been flagged automatically
+    ///  because the game has been won
+    pub flagged: Vec<Cell>,
+}
+
+/// Summary of the lost game which tells the player
+///  which mine exploded and how good the flags were
+///
+/// The flag is correct if it has the same kind and count
+///  as mines of the cell
+#[derive(Clone, Debug, PartialEq)]
+pub struct LossReport {
+    /// The mine which has taken the last life
+    pub detonated: Cell,
+
+    /// Flagged cells which don't contain such mines,
+    ///  their state becomes `WrongFlag`
+    pub wrong_flags: Vec<Cell>,
+
+    /// Flagged cells which contain such mines,
+    ///  they stay flagged
+    pub correct_flags: Vec<Cell>,
+
+    /// Mines which were neither flagged nor hit,
+    ///  they are revealed
+    pub unflagged_mines: Vec<Cell>,
 }
 
-/// Public interface for the Battlefield
+/// Public interface for the built-in battlefields
 impl BattleField {
     /// Creates an empty battlefield map with
     ///  no mines and without any text messages
@@ -32,248 +141,369 @@ impl BattleField {
     /// Notes
     ///  But it should place bombs and text messages recording to the bombs
     pub fn new(rows: usize, cols: usize, bombs: u16) -> Self {
-        let mut battlefield_map = Vec::with_capacity(cols);
+        Self::from_config(&GameConfig::new(rows, cols, bombs))
+    }
 
-        // Calculates bomb positions in random place on the map
-        let mut bombs_map = Vec::<CellPosition>::with_capacity(bombs as usize);
-        for _ in 0..bombs {
-            let mut bomb_position = Self::get_random_bomb_location(cols, rows);
-
-            // If we have the same position which already has
-            //  in `bombs_map` vector we have to re-create bomb location
-            //  to avoid duplications
-            for bomb_pos in bombs_map.iter() {
-                if bomb_pos == &bomb_position {
-                    bomb_position = Self::get_random_bomb_location(cols, rows);
-                }
-            }
+    /// Creates a battlefield the same way as `new` does but
+    ///  places bombs based on provided `seed`, so the same
+    ///  `seed` always produces the same map
+    pub fn with_seed(rows: usize, cols: usize, bombs: u16, seed: u64) -> Self {
+        Self::from_config(&GameConfig::new(rows, cols, bombs).with_seed(seed))
+    }
 
-            bombs_map.push(bomb_position);
+    /// Creates a battlefield by provided config
+    ///
+    /// Panics if the config is invalid, use `GameConfig::validate`
+    ///  to check configs which come from the player
+    pub fn from_config(config: &GameConfig) -> Self {
+        if let Err(error) = config.validate() {
+            panic!("Battlefield can't be created: {}", error);
         }
 
-        let mut unique_id = 0;
-        for col_index in 0..cols {
-            battlefield_map.push(Vec::with_capacity(rows));
+        let grid = Grid::new(config);
+        let mines = Mines {
+            bombs: config.bombs,
+            negative_bombs: config.negative_bombs,
+            max_mines_per_cell: config.max_mines_per_cell,
+        };
+
+        let mut battlefield = match config.seed {
+            Some(seed) => Self::generate(grid, mines, &mut StdRng::seed_from_u64(seed)),
+            None => Self::generate(grid, mines, &mut rand::thread_rng()),
+        };
+        battlefield.lives = config.lives.max(1);
+        battlefield.lives_left = battlefield.lives;
+
+        battlefield
+    }
 
-            for row_index in 0..rows {
-                let mut ctype = CellType::Empty(0);
-                for bomb_position in bombs_map.iter() {
-                    if bomb_position.y == row_index as i16 && bomb_position.x == col_index as i16 {
-                        ctype = CellType::Mine;
-                    }
-                }
+    /// Returns a link to the cell by provided `coordinates`
+    ///  or `None` if they are outside of the map
+    pub fn get_by_coordinates(&self, coordinates: CellCoordinates) -> Option<&Cell> {
+        if self.topology.is_inside(coordinates) {
+            self.get_by_position(self.topology.position(coordinates))
+        } else {
+            None
+        }
+    }
 
-                battlefield_map[col_index].push(Cell::new(
-                    unique_id,
-                    ctype,
-                    CellPosition {
-                        x: col_index as i16,
-                        y: row_index as i16,
-                    },
-                ));
+    /// Returns the cell by provided `coordinates` or the error
+    ///  if they are outside of the map or the cell doesn't exist
+    pub fn existing_cell(&self, coordinates: CellCoordinates) -> Result<&Cell, String> {
+        let CellCoordinates { x, y, z } = coordinates;
+
+        match self.get_by_coordinates(coordinates) {
+            Some(cell) if cell.state != CellState::Void => Ok(cell),
+            Some(_) => Err(format!(
+                "cell (row: {}, col: {}, layer: {}) doesn't exist",
+                y, x, z
+            )),
+            None => Err(format!(
+                "cell (row: {}, col: {}, layer: {}) is outside of the battlefield {}x{}x{}",
+                y,
+                x,
+                z,
+                self.rows(),
+                self.cols(),
+                self.layers()
+            )),
+        }
+    }
 
-                unique_id += 1;
-            }
+    /// Returns all cells of the layer `z` where the first vector
+    ///  is a `x` axis and the second one is a `y` axis
+    pub fn layer(&self, z: usize) -> Vec<&[Cell]> {
+        let rows = self.rows();
+
+        if z >= self.layers() {
+            return vec![];
         }
 
-        // Update counts for each cell which are nearby bombs
-        for bomb_position in bombs_map.iter() {
-            for col in -1..2 {
-                for row in -1..2 {
-                    let position_x = bomb_position.x + col;
-                    let position_y = bomb_position.y + row;
+        self.map
+            .iter()
+            .map(|col| &col[z * rows..(z + 1) * rows])
+            .collect()
+    }
 
-                    // Check if position for current cell inside the map
-                    //  not less than 0
-                    //  and not more than number of cols and rows
-                    let is_position_inside_map = position_x >= 0
-                        && position_y >= 0
-                        && position_x < cols as i16
-                        && position_y < rows as i16;
+    /// Returns how many columns the battlefield has
+    pub fn cols(&self) -> usize {
+        self.topology.cols()
+    }
 
-                    if is_position_inside_map {
-                        let x = position_x as usize;
-                        let y = position_y as usize;
+    /// Returns how many rows every layer of the battlefield has
+    pub fn rows(&self) -> usize {
+        self.topology.rows()
+    }
 
-                        let cell = &mut battlefield_map[x][y];
+    /// Returns how many layers the battlefield has
+    pub fn layers(&self) -> usize {
+        self.topology.layers()
+    }
 
-                        let new_type = match cell.ctype {
-                            CellType::Mine => CellType::Mine,
-                            CellType::Empty(count) => CellType::Empty(count + 1),
-                        };
+    /// Returns the config which creates the same kind of battlefield
+    ///
+    /// The seed isn't known by the battlefield, so it's always `None`
+    pub fn config(&self) -> GameConfig {
+        self.topology
+            .config(self.bombs)
+            .with_negative_bombs(self.negative_bombs)
+            .with_max_mines_per_cell(self.max_mines_per_cell)
+            .with_lives(self.lives)
+    }
+}
 
-                        cell.ctype = new_type;
-                    }
-                }
+/// Public interface for battlefields of any geometry
+impl<T: Topology> BattleField<T> {
+    /// Creates a battlefield of provided geometry
+    ///  and places `bombs` in random cells
+    pub fn from_topology(topology: T, bombs: u16) -> Self {
+        Self::generate(topology, Mines::classic(bombs), &mut rand::thread_rng())
+    }
+
+    /// Creates a battlefield the same way as `from_topology` does
+    ///  but places bombs based on provided `seed`
+    pub fn from_topology_with_seed(topology: T, bombs: u16, seed: u64) -> Self {
+        Self::generate(
+            topology,
+            Mines::classic(bombs),
+            &mut StdRng::seed_from_u64(seed),
+        )
+    }
+
+    /// Hides all cells again and returns all flags back
+    ///  so the same map may be played from the start
+    pub fn restart(&mut self) {
+        for cell in self.map.iter_mut().flatten() {
+            if cell.state != CellState::Void {
+                cell.state = CellState::Hidden;
             }
         }
 
-        Self {
-            map: battlefield_map,
-            flags_left: bombs,
-            bombs,
-        }
+        self.flags_left = self.bombs + self.negative_bombs;
+        self.lives_left = self.lives;
     }
 
     /// Reveals the cell by provided `id`
     /// Returns a vector of cells which were revealed
     ///  based on internal logic when we have to
     ///  reveal all cells which have `0` value
+    ///
+    /// A hit mine takes a life, it explodes and the game goes on
+    ///  until the last life is taken
     pub fn reveal(&mut self, cell_id: CellId) -> Reveal {
+        let state = self.get(cell_id).state;
+
+        if state == CellState::Void || state == CellState::Exploded {
+            return Reveal::default();
+        }
+
         // Create accumulator to save all revealed Cells
         let mut revealed_cells_accumulator = vec![];
         self.reveal_recursively(cell_id, &mut revealed_cells_accumulator);
 
-        let option_bomb = revealed_cells_accumulator
-            .iter()
-            .find(|cell| cell.ctype == CellType::Mine);
+        self.complete_reveal(revealed_cells_accumulator)
+    }
 
-        // If we found a bomb we have to move through all
-        //  cells, reveal it and return the actual data into
-        //  the client
-        if option_bomb.is_some() {
-            // let mut result = Vec::new();
-            for col in self.map.iter_mut() {
-                for cell in col {
-                    // Do not reveal already revealed cells
-                    if cell.state != CellState::Revealed {
-                        // Update cell state
-                        cell.reveal();
-                        revealed_cells_accumulator.push(*cell);
-                    }
-                }
-            }
+    /// Chords the cell by provided `id`
+    ///
+    /// If the cell is revealed and it has exactly as many flags
+    ///  around as its value, all other hidden neighbours
+    ///  are revealed at once. Otherwise nothing happens and
+    ///  an empty `Reveal` is returned
+    ///
+    /// Negative flags subtract from the count of flags
+    ///  the same way as negative mines subtract from values,
+    ///  but at least one flag must be around the cell
+    pub fn chord(&mut self, cell_id: CellId) -> Reveal {
+        let cell = *self.get(cell_id);
+
+        let value = match (cell.state, cell.ctype) {
+            (CellState::Revealed, CellType::Empty(value)) => value,
+            _ => return Reveal::default(),
+        };
+
+        let neighbours = self.neighbours(cell.position);
+        let flags_around = neighbours
+            .iter()
+            .map(|cell| cell.flag_charge())
+            .sum::<i16>();
+        let is_flagged_around = neighbours.iter().any(|cell| cell.flags() > 0);
 
-            let flags_left = self.flags_left();
-            self.flags_left = flags_left;
+        if flags_around != value as i16 || !is_flagged_around {
+            return Reveal::default();
+        }
 
-            Reveal {
-                game_is_over: true,
-                cells: revealed_cells_accumulator,
-            }
-        } else {
-            let flags_left = self.flags_left();
-            self.flags_left = flags_left;
+        let hidden_neighbours = neighbours
+            .iter()
+            .filter(|cell| cell.state == CellState::Hidden)
+            .map(|cell| cell.id)
+            .collect::<Vec<CellId>>();
 
-            Reveal {
-                game_is_over: false,
-                cells: revealed_cells_accumulator,
+        let mut revealed_cells_accumulator = vec![];
+        for neighbour_id in hidden_neighbours {
+            // The cell might be already revealed by the
+            //  flood fill of one of the previous neighbours
+            if self.get(neighbour_id).state == CellState::Hidden {
+                self.reveal_recursively(neighbour_id, &mut revealed_cells_accumulator);
             }
         }
+
+        self.complete_reveal(revealed_cells_accumulator)
     }
 
     /// Flag the cell by provided `CellId` and
     ///  returns the Cell
+    ///
+    /// Cells which may contain several mines cycle through
+    ///  `1..=max_mines_per_cell` flags before they are unflagged,
+    ///  when negative mines are placed the cell cycles through
+    ///  the same count of negative flags after that
     pub fn flag(&mut self, cell_id: CellId) -> &Cell {
-        let cell = self.get(cell_id);
-        let is_flagged = cell.state == CellState::Flagged;
-
-        // User wants to unflag the cell
-        //  we may do that without any restrictions
-        if is_flagged {
-            self.flags_left += 1;
+        let max_flags = self.max_mines_per_cell;
+        let has_negative_bombs = self.negative_bombs > 0;
+        let flags_left = self.flags_left;
 
-            let cell = self.get_mut(cell_id);
-            cell.flag();
+        let cell = self.get_mut(cell_id);
+        let flags = cell.state.flags();
 
-            cell
+        // Revealed cells can't be flagged so flags
+        //  are spent only if the cell has been flagged
+        if has_negative_bombs {
+            cell.flag_with_negative_up_to(max_flags);
         } else {
-            // User wants to flag the cell
-            //  we have to check if is it possible or not
+            cell.flag_up_to(max_flags);
+        }
 
-            // We can't flag the cell
-            if self.flags_left == 0 {
-                self.get_mut(cell_id)
-            } else {
-                self.flags_left -= 1;
+        // We can't add more flags than left, so the cell
+        //  is unflagged to keep cycling
+        if cell.state.flags().saturating_sub(flags) as u16 > flags_left {
+            cell.state = CellState::Hidden;
+        }
 
-                let cell = self.get_mut(cell_id);
-                cell.flag();
+        let new_flags = cell.state.flags();
+        self.flags_left = flags_left + flags as u16 - new_flags as u16;
 
-                cell
-            }
-        }
+        self.get(cell_id)
     }
 
-    /// Returns a mutable link to the cell by provided `id`
-    pub fn get_mut(&mut self, id: CellId) -> &mut Cell {
-        for row in &mut self.map {
-            for cell in row {
-                if cell.id == id {
-                    return cell;
-                }
+    /// Flags every mine which isn't flagged yet or is flagged wrong
+    ///  and returns changed cells, so no flags are left after that
+    ///
+    /// Used to finalize the won battlefield
+    pub fn flag_all_mines(&mut self) -> Vec<Cell> {
+        let mut flagged = vec![];
+
+        for cell in self.map.iter_mut().flatten() {
+            let flags = match cell.ctype {
+                CellType::Mine(mines) => CellState::Flagged(mines),
+                CellType::NegativeMine(mines) => CellState::NegativeFlagged(mines),
+                CellType::Empty(_) => continue,
+            };
+
+            if cell.state != flags && cell.state != CellState::Exploded {
+                cell.state = flags;
+                flagged.push(*cell);
             }
         }
 
-        panic!("Cell didn't find in battlefield by provided id: {}", id);
-    }
+        let flags_left = self.flags_left();
+        self.flags_left = flags_left;
 
-    /// Returns all matrix map
-    pub fn get_all(&self) -> &Vec<Vec<Cell>> {
-        &self.map
+        flagged
     }
-}
-
-/// Private interface for the Battlefield
-impl BattleField {
-    /// Generates random bomb location by giving `cols` and `rows`
-    fn get_random_bomb_location(cols: usize, rows: usize) -> CellPosition {
-        let mut rng = rand::thread_rng();
-
-        let random_bomb_col = rng.gen_range(0..cols);
-        let random_bomb_row = rng.gen_range(0..rows);
 
-        CellPosition {
-            x: random_bomb_col as i16,
-            y: random_bomb_row as i16,
-        }
+    /// Returns `true` if every mine has exactly as many flags
+    ///  of its kind as it contains and no empty cell is flagged
+    ///
+    /// Exploded mines are known to the player, so they count as flagged
+    pub fn are_all_mines_flagged(&self) -> bool {
+        self.map.iter().flatten().all(|cell| match cell.ctype {
+            CellType::Mine(mines) => {
+                cell.state == CellState::Flagged(mines) || cell.state == CellState::Exploded
+            }
+            CellType::NegativeMine(mines) => {
+                cell.state == CellState::NegativeFlagged(mines) || cell.state == CellState::Exploded
+            }
+            CellType::Empty(_) => !cell.state.is_flagged(),
+        })
     }
 
-    /// Reveals the cell and iteratively execute `flood_fill` method
-    ///  to calculate all near cells and reveal them too if
-    ///  they have an `Empty` status and the value of the
-    ///  cell is `0`
-    fn reveal_recursively(&mut self, cell_id: CellId, accumulator: &mut Vec<Cell>) {
-        let cell = self.get_mut(cell_id);
-        cell.reveal();
-        accumulator.push(*cell);
-
-        if cell.ctype == CellType::Empty(0) {
-            let position = cell.position;
-
-            self.flood_fill(position, accumulator);
+    /// Moves mines between unopened cells, so the cell with `cell_id`
+    ///  contains a mine or not as requested by `is_mine`, while
+    ///  values of all revealed cells and the count of mines stay the same
+    ///
+    /// Returns `false` and changes nothing if there is no such layout,
+    ///  see `Solver::find_layout`
+    pub fn relocate_mines(&mut self, cell_id: CellId, is_mine: bool) -> bool {
+        let layout = match Solver::new(self).find_layout(cell_id, is_mine) {
+            Some(layout) => layout.into_iter().collect::<HashSet<CellId>>(),
+            None => return false,
+        };
+
+        for cell in self.map.iter_mut().flatten() {
+            if cell.state.is_unopened() {
+                cell.ctype = if layout.contains(&cell.id) {
+                    CellType::Mine(1)
+                } else {
+                    CellType::Empty(0)
+                };
+            }
         }
+        self.recount_values();
+
+        true
     }
 
-    /// Calculates all near cells based on `cell_position` and call
-    ///  `reveal_priv` to reveal these cells if they have `0` value
+    /// Puts provided count of mines into cells on provided positions,
+    ///  `0` makes the cell empty, then recounts values of empty cells
+    ///  and counters of mines and flags
     ///
-    /// @see https://en.wikipedia.org/wiki/Flood_fill
-    fn flood_fill(&mut self, cell_position: CellPosition, accumulator: &mut Vec<Cell>) {
-        for col in -1..2 {
-            for row in -1..2 {
-                let position = CellPosition {
-                    x: cell_position.x + col,
-                    y: cell_position.y + row,
-                };
-                let option_cell = self.get_by_position(position);
+    /// Positions must be inside of the map, it's used by `BoardEditor`
+    ///  which checks them
+    pub(crate) fn set_mines(&mut self, mines: &[(CellPosition, u8)]) {
+        for (position, count) in mines {
+            let cell = &mut self.map[position.x as usize][position.y as usize];
+
+            cell.ctype = match count {
+                0 => CellType::Empty(0),
+                count => CellType::Mine(*count),
+            };
+            self.max_mines_per_cell = self.max_mines_per_cell.max(*count);
+        }
 
-                if let Some(cell) = option_cell {
-                    if cell.ctype != CellType::Mine && cell.state != CellState::Revealed {
-                        let cell_id = cell.id;
+        let count_mines = |is_negative: bool| {
+            self.map
+                .iter()
+                .flatten()
+                .filter(|cell| matches!(cell.ctype, CellType::NegativeMine(_)) == is_negative)
+                .map(|cell| cell.ctype.mines() as u16)
+                .sum::<u16>()
+        };
+        let (bombs, negative_bombs) = (count_mines(false), count_mines(true));
+
+        self.bombs = bombs;
+        self.negative_bombs = negative_bombs;
+        self.flags_left = self.flags_left();
+
+        self.recount_values();
+    }
 
-                        // If cell is not a mine, and it's not revealed
-                        // we have to call `reveal` method again
-                        self.reveal_recursively(cell_id, accumulator);
-                    }
+    /// Returns immutable link to the cell by provided `id`
+    pub fn get(&self, id: CellId) -> &Cell {
+        for row in &self.map {
+            for cell in row {
+                if cell.id == id {
+                    return cell;
                 }
             }
         }
+
+        panic!("Cell didn't find in battlefield by provided id: {}", id);
     }
 
-    /// Returns immutable link to the cell by provided `id`
-    fn get(&self, id: CellId) -> &Cell {
-        for row in &self.map {
+    /// Returns a mutable link to the cell by provided `id`
+    pub fn get_mut(&mut self, id: CellId) -> &mut Cell {
+        for row in &mut self.map {
             for cell in row {
                 if cell.id == id {
                     return cell;
@@ -284,8 +514,13 @@ impl BattleField {
         panic!("Cell didn't find in battlefield by provided id: {}", id);
     }
 
-    /// Returns a link to the cell by provided `id`
-    fn get_by_position(&self, position: CellPosition) -> Option<&Cell> {
+    /// Returns a link to the cell by provided `position`
+    ///  or `None` if the position is outside of the map
+    pub fn get_by_position(&self, position: CellPosition) -> Option<&Cell> {
+        if position.x < 0 || position.y < 0 {
+            return None;
+        }
+
         if let Some(column) = self.map.get(position.x as usize) {
             if let Some(cell) = column.get(position.y as usize) {
                 Some(cell)
@@ -297,119 +532,573 @@ impl BattleField {
         }
     }
 
-    /// Returns count of how many flags are left
-    ///
-    /// Note: It's very consumable method which
-    ///  iterate through all cells on the battlefield
-    ///  and count how many cells aren't flagged
-    fn flags_left(&self) -> u16 {
-        let mut flags_left = self.bombs;
+    /// Returns all matrix map
+    pub fn get_all(&self) -> &Vec<Vec<Cell>> {
+        &self.map
+    }
 
-        for row in &self.map {
-            for cell in row {
-                if cell.state == CellState::Flagged {
-                    flags_left -= 1;
+    /// Returns all cells which are counted by the value of the cell
+    ///  on provided `position` without the cell itself
+    ///  and cells which don't exist
+    ///
+    /// Every neighbour is returned once even if the topology
+    ///  reaches it from several sides
+    pub fn neighbours(&self, position: CellPosition) -> Vec<&Cell> {
+        let neighbour_positions = self.topology.neighbours(position);
+        let mut neighbours = Vec::<&Cell>::with_capacity(neighbour_positions.len());
+
+        for neighbour_position in neighbour_positions {
+            if let Some(cell) = self.get_by_position(neighbour_position) {
+                let is_known = cell.state == CellState::Void
+                    || cell.position == position
+                    || neighbours.iter().any(|neighbour| neighbour.id == cell.id);
+
+                if !is_known {
+                    neighbours.push(cell);
                 }
             }
         }
 
-        flags_left
+        neighbours
     }
-}
-
-/// Battlefield configuration for tests
-#[cfg(test)]
-impl BattleField {
-    /// Creates a battlefield with provided map
-    ///  works only for `test` scenario
-    fn with_map(map: BattlefieldMap) -> Self {
-        let bombs_count = map.iter().fold(0, |outer_acc, row| {
-            outer_acc
-                + row.iter().fold(0, |inner_acc, cell| {
-                    if cell.ctype == CellType::Mine {
-                        inner_acc + 1
-                    } else {
-                        inner_acc
-                    }
-                })
-        });
 
-        Self {
-            map,
-            flags_left: bombs_count,
-            bombs: bombs_count,
-        }
+    /// Returns the geometry of the map
+    pub fn topology(&self) -> &T {
+        &self.topology
     }
-}
 
-#[test]
-fn should_create_field_4_by_10() {
-    let battlefield = BattleField::new(10, 4, 0);
-    let field = battlefield.get_all();
+    /// Returns how many cells the map has
+    ///  including cells which don't exist
+    pub fn cells_count(&self) -> usize {
+        self.map.iter().map(|col| col.len()).sum()
+    }
 
-    assert_eq!(field.len(), 4);
-    assert_eq!(field[0].len(), 10);
-}
+    /// Returns how many bombs has been placed on the map
+    pub fn bombs(&self) -> u16 {
+        self.bombs
+    }
 
-#[test]
-fn field_should_contains_10_bombs() {
-    let battlefield = BattleField::new(10, 10, 10);
-    let field = battlefield.get_all();
+    /// Returns how many negative bombs has been placed on the map
+    pub fn negative_bombs(&self) -> u16 {
+        self.negative_bombs
+    }
 
-    let mut bombs_count = 0;
-    for col in field.iter() {
-        for cell in col {
-            if cell.ctype == CellType::Mine {
-                bombs_count += 1;
-            }
-        }
+    /// Returns how many mines a single cell may contain
+    pub fn max_mines_per_cell(&self) -> u8 {
+        self.max_mines_per_cell
     }
 
-    assert_eq!(bombs_count, 10);
-}
+    /// Returns how many mines the player may hit before the game is lost
+    pub fn lives(&self) -> u8 {
+        self.lives
+    }
 
-#[test]
-fn should_return_cell_by_specify_position() {
-    let battlefield = BattleField::new(10, 10, 0);
-    let cell = battlefield.get_by_position(CellPosition { x: 1, y: 5 });
+    /// Returns how many cells contain at least one mine,
+    ///  it's less than `bombs` when cells contain several mines
+    pub fn mined_cells_count(&self) -> usize {
+        self.map
+            .iter()
+            .flatten()
+            .filter(|cell| cell.ctype.is_mine())
+            .count()
+    }
 
-    if let Some(c) = cell {
-        assert_eq!(
-            *c,
-            Cell::new(15, CellType::Empty(0), CellPosition { x: 1, y: 5 }),
-        )
+    /// Returns how many cells of the map don't exist
+    pub fn void_cells_count(&self) -> usize {
+        self.map
+            .iter()
+            .flatten()
+            .filter(|cell| cell.state == CellState::Void)
+            .count()
     }
-}
 
-#[test]
-fn should_not_return_cell_by_specify_position_because_it_is_out_of_bounce() {
-    let battlefield = BattleField::new(10, 10, 0);
-    let cell = battlefield.get_by_position(CellPosition { x: -3, y: -1 });
+    /// Returns 3BV (Bechtel's Board Benchmark Value) of the map
+    ///  which is the minimal number of clicks to clear it
+    ///  without flagging: every opening (an area of cells without
+    ///  mines around with its border) takes one click and every other
+    ///  empty cell outside of openings takes one click too
+    pub fn three_bv(&self) -> u32 {
+        let mut marked = HashSet::<CellId>::new();
+        let mut three_bv = 0;
+
+        for cell in self.map.iter().flatten() {
+            if !self.is_opening(cell) || marked.contains(&cell.id) {
+                continue;
+            }
 
-    if cell.is_some() {
-        panic!("Cell mustn't be Some in that particular scenario");
-    } else {
-        assert!(cell.is_none());
+            three_bv += 1;
+
+            // Marks the whole opening which the cell belongs to
+            let mut openings = vec![*cell];
+            marked.insert(cell.id);
+
+            while let Some(opening) = openings.pop() {
+                for neighbour in self.neighbours(opening.position) {
+                    if marked.insert(neighbour.id) && self.is_opening(neighbour) {
+                        openings.push(*neighbour);
+                    }
+                }
+            }
+        }
+
+        for cell in self.map.iter().flatten() {
+            if !cell.ctype.is_mine() && cell.state != CellState::Void && !marked.contains(&cell.id)
+            {
+                three_bv += 1;
+            }
+        }
+
+        three_bv
     }
 }
 
-#[test]
-fn should_reveal_all_cells_if_none_bombs_were_found() {
-    // |0, 0, 0|
-    // |0, 0, 0|
-    // |0, 0, 0|
-    let map = vec![
-        vec![
-            Cell::new(0, CellType::Empty(0), CellPosition { x: 0, y: 0 }),
-            Cell::new(1, CellType::Empty(0), CellPosition { x: 0, y: 1 }),
-            Cell::new(2, CellType::Empty(0), CellPosition { x: 0, y: 2 }),
-        ],
-        vec![
-            Cell::new(3, CellType::Empty(0), CellPosition { x: 1, y: 0 }),
-            Cell::new(4, CellType::Empty(0), CellPosition { x: 1, y: 1 }),
-            Cell::new(5, CellType::Empty(0), CellPosition { x: 1, y: 2 }),
-        ],
-        vec![
+/// Private interface for the Battlefield
+impl<T: Topology> BattleField<T> {
+    /// Creates the battlefield map of provided geometry and places
+    ///  `mines` on it by using provided random number generator
+    fn generate<R: Rng>(topology: T, mines: Mines, rng: &mut R) -> Self {
+        let mines = mines.bounded(&topology);
+        let (rows, cols) = (topology.height(), topology.width());
+        let mut battlefield_map = Vec::with_capacity(cols);
+
+        // Calculates bomb positions in random place on the map,
+        //  negative bombs never share cells with bombs
+        let bombs_map = Self::place_bombs(&topology, mines.bombs, mines, &[], rng);
+        let negative_bombs_map =
+            Self::place_bombs(&topology, mines.negative_bombs, mines, &bombs_map, rng);
+
+        let mut unique_id = 0;
+        for col_index in 0..cols {
+            battlefield_map.push(Vec::with_capacity(rows));
+
+            for row_index in 0..rows {
+                let position = CellPosition {
+                    x: col_index as i16,
+                    y: row_index as i16,
+                };
+                let count = |positions: &[CellPosition]| {
+                    positions
+                        .iter()
+                        .filter(|bomb_position| **bomb_position == position)
+                        .count() as u8
+                };
+
+                let ctype = match (count(&bombs_map), count(&negative_bombs_map)) {
+                    (0, 0) => CellType::Empty(0),
+                    (0, negative_mines) => CellType::NegativeMine(negative_mines),
+                    (mines, _) => CellType::Mine(mines),
+                };
+
+                let mut cell = Cell::new(unique_id, ctype, position);
+
+                if !topology.contains(cell.position) {
+                    cell.state = CellState::Void;
+                }
+
+                battlefield_map[col_index].push(cell);
+
+                unique_id += 1;
+            }
+        }
+
+        let mut battlefield = Self {
+            map: battlefield_map,
+            flags_left: (bombs_map.len() + negative_bombs_map.len()) as u16,
+            bombs: bombs_map.len() as u16,
+            negative_bombs: negative_bombs_map.len() as u16,
+            max_mines_per_cell: mines.max_mines_per_cell,
+            lives: 1,
+            lives_left: 1,
+            topology,
+        };
+        battlefield.recount_values();
+
+        battlefield
+    }
+
+    /// Sets values of all empty cells by mines around them
+    fn recount_values(&mut self) {
+        // Every empty cell counts mines among its own neighbours,
+        //  which isn't the same as cells around mines when
+        //  the neighbourhood isn't symmetric, every mine
+        //  of multi-mine cells is counted and negative
+        //  mines are subtracted
+        let values = self
+            .map
+            .iter()
+            .flatten()
+            .map(|cell| {
+                self.neighbours(cell.position)
+                    .iter()
+                    .map(|neighbour| neighbour.ctype.charge())
+                    .sum::<i16>()
+            })
+            .collect::<Vec<i16>>();
+
+        // Mines per cell are bounded by the count of neighbours,
+        //  so the value always fits
+        for (cell, value) in self.map.iter_mut().flatten().zip(values) {
+            if !cell.ctype.is_mine() {
+                cell.ctype = CellType::Empty(value as i8);
+            }
+        }
+    }
+
+    /// Picks random positions for `count` bombs, every cell gets
+    ///  up to `max_mines_per_cell` of them and cells from `occupied`
+    ///  or cells which don't exist don't get any
+    ///
+    /// Positions are sampled from free places of cells without retries,
+    ///  so if cells can't take `count` bombs only as many bombs
+    ///  as they can take are returned
+    fn place_bombs<R: Rng>(
+        topology: &T,
+        count: u16,
+        mines: Mines,
+        occupied: &[CellPosition],
+        rng: &mut R,
+    ) -> Vec<CellPosition> {
+        let (rows, cols) = (topology.height(), topology.width());
+        let occupied = occupied.iter().collect::<HashSet<&CellPosition>>();
+        let mut places = Vec::with_capacity(rows * cols);
+
+        for x in 0..cols {
+            for y in 0..rows {
+                let position = CellPosition {
+                    x: x as i16,
+                    y: y as i16,
+                };
+
+                if topology.contains(position) && !occupied.contains(&position) {
+                    for _ in 0..mines.max_mines_per_cell {
+                        places.push(position);
+                    }
+                }
+            }
+        }
+
+        let count = (count as usize).min(places.len());
+
+        rand::seq::index::sample(rng, places.len(), count)
+            .into_iter()
+            .map(|index| places[index])
+            .collect()
+    }
+
+    /// Finishes revealing of the cells from `accumulator`
+    ///
+    /// Every revealed bomb takes a life and explodes, if the last one
+    ///  is taken the game is over and all other cells must be revealed too
+    fn complete_reveal(&mut self, mut revealed_cells_accumulator: Vec<Cell>) -> Reveal {
+        // Mines which have been hit explode and stay
+        //  on the map as flagged
+        for cell in revealed_cells_accumulator.iter_mut() {
+            if cell.ctype.is_mine() {
+                cell.state = CellState::Exploded;
+                self.get_mut(cell.id).state = CellState::Exploded;
+            }
+        }
+
+        // The first bomb is the one which was hit by the player,
+        //  others might be revealed by the same chord
+        let detonated = revealed_cells_accumulator
+            .iter()
+            .find(|cell| cell.ctype.is_mine())
+            .copied();
+        let hit_mines = revealed_cells_accumulator
+            .iter()
+            .filter(|cell| cell.ctype.is_mine())
+            .count();
+
+        // Every hit mine takes a life
+        self.lives_left = self.lives_left.saturating_sub(hit_mines as u8);
+
+        // If the last life has been taken we have to move through
+        //  all cells, reveal it and return the actual data into
+        //  the client
+        let loss = match detonated {
+            Some(detonated) if self.lives_left == 0 => {
+                Some(self.lose(detonated, &mut revealed_cells_accumulator))
+            }
+            _ => None,
+        };
+
+        let flags_left = self.flags_left();
+        self.flags_left = flags_left;
+
+        Reveal {
+            game_is_over: loss.is_some(),
+            cells: revealed_cells_accumulator,
+            loss,
+            flagged: vec![],
+        }
+    }
+
+    /// Reveals all cells after the last life has been taken by
+    ///  the `detonated` mine, marks wrong flags and keeps correct ones
+    ///
+    /// All changed cells are put into the `accumulator`
+    fn lose(&mut self, detonated: Cell, accumulator: &mut Vec<Cell>) -> LossReport {
+        let mut report = LossReport {
+            detonated,
+            wrong_flags: vec![],
+            correct_flags: vec![],
+            unflagged_mines: vec![],
+        };
+
+        for cell in self.map.iter_mut().flatten() {
+            match cell.state {
+                CellState::Flagged(_) | CellState::NegativeFlagged(_) => {
+                    if cell.state.charge() == cell.ctype.charge() {
+                        report.correct_flags.push(*cell);
+                    } else {
+                        cell.state = CellState::WrongFlag;
+                        report.wrong_flags.push(*cell);
+                        accumulator.push(*cell);
+                    }
+                }
+                CellState::Hidden => {
+                    cell.reveal();
+                    accumulator.push(*cell);
+
+                    if cell.ctype.is_mine() {
+                        report.unflagged_mines.push(*cell);
+                    }
+                }
+                // Do not reveal already revealed cells
+                _ => {}
+            }
+        }
+
+        report
+    }
+
+    /// Reveals the cell and iteratively execute `flood_fill` method
+    ///  to calculate all near cells and reveal them too if
+    ///  they have an `Empty` status and there are no mines
+    ///  of any kind around the cell
+    fn reveal_recursively(&mut self, cell_id: CellId, accumulator: &mut Vec<Cell>) {
+        let cell = self.get_mut(cell_id);
+        cell.reveal();
+        accumulator.push(*cell);
+
+        let cell = *cell;
+        if self.is_opening(&cell) {
+            self.flood_fill(cell.position, accumulator);
+        }
+    }
+
+    /// Returns `true` if the cell is empty and there are no mines
+    ///  of any kind around it
+    ///
+    /// Values of such cells are always `0` but the cell with `0`
+    ///  may have negative and positive mines around which
+    ///  compensate each other, so neighbours are checked
+    ///  only if negative mines are placed
+    fn is_opening(&self, cell: &Cell) -> bool {
+        cell.ctype == CellType::Empty(0)
+            && (self.negative_bombs == 0
+                || !self
+                    .neighbours(cell.position)
+                    .iter()
+                    .any(|neighbour| neighbour.ctype.is_mine()))
+    }
+
+    /// Calculates all near cells based on `cell_position` and call
+    ///  `reveal_priv` to reveal these cells if they have `0` value
+    ///
+    /// @see https://en.wikipedia.org/wiki/Flood_fill
+    fn flood_fill(&mut self, cell_position: CellPosition, accumulator: &mut Vec<Cell>) {
+        let neighbour_ids = self
+            .neighbours(cell_position)
+            .iter()
+            .map(|cell| cell.id)
+            .collect::<Vec<CellId>>();
+
+        for cell_id in neighbour_ids {
+            let cell = self.get(cell_id);
+
+            // If cell is not a mine, and it's not revealed
+            //  we have to call `reveal` method again
+            if !cell.ctype.is_mine() && cell.state != CellState::Revealed {
+                self.reveal_recursively(cell_id, accumulator);
+            }
+        }
+    }
+
+    /// Returns count of how many flags are left
+    ///
+    /// Note: It's very consumable method which
+    ///  iterate through all cells on the battlefield
+    ///  and count how many flags are set
+    ///
+    /// Exploded mines take flags too, so wrong flags of the player
+    ///  may leave no flags at all
+    fn flags_left(&self) -> u16 {
+        let mut flags_left = self.bombs + self.negative_bombs;
+
+        for row in &self.map {
+            for cell in row {
+                flags_left = flags_left.saturating_sub(cell.flags() as u16);
+            }
+        }
+
+        flags_left
+    }
+}
+
+/// Battlefield configuration for tests
+#[cfg(test)]
+impl BattleField {
+    /// Creates a battlefield with provided map
+    ///  works only for `test` scenario
+    pub(crate) fn with_map(map: BattlefieldMap) -> Self {
+        let count_mines = |is_negative: bool| {
+            map.iter()
+                .flatten()
+                .filter(|cell| matches!(cell.ctype, CellType::NegativeMine(_)) == is_negative)
+                .map(|cell| cell.ctype.mines() as u16)
+                .sum::<u16>()
+        };
+        let (bombs_count, negative_bombs_count) = (count_mines(false), count_mines(true));
+        let max_mines_per_cell = map
+            .iter()
+            .flatten()
+            .map(|cell| cell.ctype.mines())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let config = GameConfig::new(map.first().map_or(0, |col| col.len()), map.len(), 0);
+
+        Self {
+            map,
+            flags_left: bombs_count + negative_bombs_count,
+            bombs: bombs_count,
+            negative_bombs: negative_bombs_count,
+            max_mines_per_cell,
+            lives: 1,
+            lives_left: 1,
+            topology: Grid::new(&config),
+        }
+    }
+}
+
+/// Asserts that every empty cell counts mines around it,
+///  negative mines are subtracted
+#[cfg(test)]
+pub(crate) fn assert_values<T: Topology>(battlefield: &BattleField<T>) {
+    for cell in battlefield.get_all().iter().flatten() {
+        if let CellType::Empty(value) = cell.ctype {
+            let charge = battlefield
+                .neighbours(cell.position)
+                .iter()
+                .map(|neighbour| neighbour.ctype.charge())
+                .sum::<i16>();
+
+            assert_eq!(value as i16, charge, "value of cell {}", cell.id);
+        }
+    }
+}
+
+#[test]
+fn should_create_field_4_by_10() {
+    let battlefield = BattleField::new(10, 4, 0);
+    let field = battlefield.get_all();
+
+    assert_eq!(field.len(), 4);
+    assert_eq!(field[0].len(), 10);
+}
+
+#[test]
+fn field_should_contains_10_bombs() {
+    let battlefield = BattleField::new(10, 10, 10);
+    let field = battlefield.get_all();
+
+    let mut bombs_count = 0;
+    for col in field.iter() {
+        for cell in col {
+            if cell.ctype.is_mine() {
+                bombs_count += 1;
+            }
+        }
+    }
+
+    assert_eq!(bombs_count, 10);
+}
+
+#[test]
+fn should_place_only_as_many_bombs_as_cells_can_take() {
+    let battlefield = BattleField::new(3, 3, 10);
+
+    assert_eq!(battlefield.bombs(), 9);
+    assert_eq!(battlefield.flags_left, 9);
+    assert_eq!(battlefield.mined_cells_count(), 9);
+
+    let mask = Mask::from_ascii("#.\n##").unwrap();
+    let config = GameConfig::new(0, 0, 10)
+        .with_mask(mask)
+        .with_max_mines_per_cell(2);
+
+    assert_eq!(BattleField::from_config(&config).bombs(), 6);
+}
+
+#[test]
+fn should_return_cell_by_specify_position() {
+    let battlefield = BattleField::new(10, 10, 0);
+    let cell = battlefield.get_by_position(CellPosition { x: 1, y: 5 });
+
+    if let Some(c) = cell {
+        assert_eq!(
+            *c,
+            Cell::new(15, CellType::Empty(0), CellPosition { x: 1, y: 5 }),
+        )
+    }
+}
+
+#[test]
+fn should_not_return_cell_by_specify_position_because_it_is_out_of_bounce() {
+    let battlefield = BattleField::new(10, 10, 0);
+    let cell = battlefield.get_by_position(CellPosition { x: -3, y: -1 });
+
+    if cell.is_some() {
+        panic!("Cell mustn't be Some in that particular scenario");
+    } else {
+        assert!(cell.is_none());
+    }
+}
+
+#[test]
+fn should_not_return_cell_by_specify_position_outside_of_the_map() {
+    let battlefield = BattleField::new(10, 5, 0);
+
+    assert!(battlefield
+        .get_by_position(CellPosition { x: 4, y: 9 })
+        .is_some());
+    assert!(battlefield
+        .get_by_position(CellPosition { x: 5, y: 0 })
+        .is_none());
+    assert!(battlefield
+        .get_by_position(CellPosition { x: 0, y: 10 })
+        .is_none());
+}
+
+#[test]
+fn should_reveal_all_cells_if_none_bombs_were_found() {
+    // |0, 0, 0|
+    // |0, 0, 0|
+    // |0, 0, 0|
+    let map = vec![
+        vec![
+            Cell::new(0, CellType::Empty(0), CellPosition { x: 0, y: 0 }),
+            Cell::new(1, CellType::Empty(0), CellPosition { x: 0, y: 1 }),
+            Cell::new(2, CellType::Empty(0), CellPosition { x: 0, y: 2 }),
+        ],
+        vec![
+            Cell::new(3, CellType::Empty(0), CellPosition { x: 1, y: 0 }),
+            Cell::new(4, CellType::Empty(0), CellPosition { x: 1, y: 1 }),
+            Cell::new(5, CellType::Empty(0), CellPosition { x: 1, y: 2 }),
+        ],
+        vec![
             Cell::new(6, CellType::Empty(0), CellPosition { x: 2, y: 0 }),
             Cell::new(7, CellType::Empty(0), CellPosition { x: 2, y: 1 }),
             Cell::new(8, CellType::Empty(0), CellPosition { x: 2, y: 2 }),
@@ -585,7 +1274,7 @@ fn should_reveal_cells_and_not_take_the_bomb() {
             Cell {
                 id: 8,
                 state: CellState::Hidden,
-                ctype: CellType::Mine,
+                ctype: CellType::Mine(1),
                 position: CellPosition { x: 2, y: 2 },
             },
         ],
@@ -686,7 +1375,7 @@ fn should_reveal_all_elements_if_user_select_the_bomb() {
             Cell {
                 id: 8,
                 state: CellState::Hidden,
-                ctype: CellType::Mine,
+                ctype: CellType::Mine(1),
                 position: CellPosition { x: 2, y: 2 },
             },
         ],
@@ -709,10 +1398,11 @@ fn should_reveal_all_elements_if_user_select_the_bomb() {
 
     revealed_cells_id.sort_unstable();
 
+    // The bomb itself comes first and it's exploded
     assert_eq!(
         revealed_cells_state,
         vec![
-            CellState::Revealed,
+            CellState::Exploded,
             CellState::Revealed,
             CellState::Revealed,
             CellState::Revealed,
@@ -738,7 +1428,7 @@ fn should_flag_the_cell() {
         Cell {
             id: 1,
             state: CellState::Hidden,
-            ctype: CellType::Mine,
+            ctype: CellType::Mine(1),
             position: CellPosition { x: 0, y: 0 },
         },
     ]];
@@ -751,7 +1441,7 @@ fn should_flag_the_cell() {
         &Cell {
             id: 0,
             ctype: CellType::Empty(0),
-            state: CellState::Flagged,
+            state: CellState::Flagged(1),
             position: CellPosition { x: 0, y: 0 }
         }
     );
@@ -780,11 +1470,34 @@ fn should_not_flag_the_cell_if_it_is_revealed() {
     );
 }
 
+#[test]
+fn should_not_spend_the_flag_on_revealed_cell() {
+    let map = vec![vec![
+        Cell {
+            id: 0,
+            state: CellState::Revealed,
+            ctype: CellType::Empty(1),
+            position: CellPosition { x: 0, y: 0 },
+        },
+        Cell {
+            id: 1,
+            state: CellState::Hidden,
+            ctype: CellType::Mine(1),
+            position: CellPosition { x: 0, y: 1 },
+        },
+    ]];
+    let mut battlefield = BattleField::with_map(map);
+
+    battlefield.flag(0);
+
+    assert_eq!(battlefield.flags_left, 1);
+}
+
 #[test]
 fn should_unflag_the_cell_if_it_is_flagged() {
     let map = vec![vec![Cell {
         id: 0,
-        state: CellState::Flagged,
+        state: CellState::Flagged(1),
         ctype: CellType::Empty(0),
         position: CellPosition { x: 0, y: 0 },
     }]];
@@ -815,13 +1528,13 @@ fn should_build_correct_max_flag_values_counter() {
         Cell {
             id: 1,
             state: CellState::Hidden,
-            ctype: CellType::Mine,
+            ctype: CellType::Mine(1),
             position: CellPosition { x: 0, y: 0 },
         },
         Cell {
             id: 2,
             state: CellState::Hidden,
-            ctype: CellType::Mine,
+            ctype: CellType::Mine(1),
             position: CellPosition { x: 0, y: 0 },
         },
     ]];
@@ -846,23 +1559,23 @@ fn should_not_set_more_flags_that_is_possible() {
         Cell {
             id: 1,
             state: CellState::Hidden,
-            ctype: CellType::Mine,
+            ctype: CellType::Mine(1),
             position: CellPosition { x: 0, y: 0 },
         },
         Cell {
             id: 2,
             state: CellState::Hidden,
-            ctype: CellType::Mine,
+            ctype: CellType::Mine(1),
             position: CellPosition { x: 0, y: 0 },
         },
     ]];
     let mut battlefield = BattleField::with_map(map);
 
     let cell0 = battlefield.flag(0);
-    assert_eq!(cell0.state, CellState::Flagged);
+    assert_eq!(cell0.state, CellState::Flagged(1));
 
     let cell1 = battlefield.flag(1);
-    assert_eq!(cell1.state, CellState::Flagged);
+    assert_eq!(cell1.state, CellState::Flagged(1));
 
     let cell2 = battlefield.flag(2);
     assert_eq!(cell2.state, CellState::Hidden);
@@ -882,13 +1595,13 @@ fn should_unflag_already_flagged_cells() {
         Cell {
             id: 1,
             state: CellState::Hidden,
-            ctype: CellType::Mine,
+            ctype: CellType::Mine(1),
             position: CellPosition { x: 0, y: 0 },
         },
         Cell {
             id: 2,
             state: CellState::Hidden,
-            ctype: CellType::Mine,
+            ctype: CellType::Mine(1),
             position: CellPosition { x: 0, y: 0 },
         },
     ]];
@@ -896,11 +1609,11 @@ fn should_unflag_already_flagged_cells() {
 
     assert_eq!(battlefield.flags_left, 2);
     let cell0 = battlefield.flag(0);
-    assert_eq!(cell0.state, CellState::Flagged);
+    assert_eq!(cell0.state, CellState::Flagged(1));
     assert_eq!(battlefield.flags_left, 1);
 
     let cell1 = battlefield.flag(1);
-    assert_eq!(cell1.state, CellState::Flagged);
+    assert_eq!(cell1.state, CellState::Flagged(1));
     assert_eq!(battlefield.flags_left, 0);
 
     // Should NOT flag the cell by `cell_id: 2`
@@ -920,7 +1633,7 @@ fn should_unflag_already_flagged_cells() {
 
     // Should flag the cell by `cell_id: 2`
     let cell2 = battlefield.flag(2);
-    assert_eq!(cell2.state, CellState::Flagged);
+    assert_eq!(cell2.state, CellState::Flagged(1));
     assert_eq!(battlefield.flags_left, 1);
 }
 
@@ -936,19 +1649,19 @@ fn should_have_actual_state_value_when_reveal_flagged_cell() {
         Cell {
             id: 1,
             state: CellState::Hidden,
-            ctype: CellType::Mine,
+            ctype: CellType::Mine(1),
             position: CellPosition { x: 0, y: 1 },
         },
         Cell {
             id: 2,
             state: CellState::Hidden,
-            ctype: CellType::Mine,
+            ctype: CellType::Mine(1),
             position: CellPosition { x: 0, y: 2 },
         },
         Cell {
             id: 3,
             state: CellState::Hidden,
-            ctype: CellType::Mine,
+            ctype: CellType::Mine(1),
             position: CellPosition { x: 0, y: 3 },
         },
     ]];
@@ -968,3 +1681,963 @@ fn should_have_actual_state_value_when_reveal_flagged_cell() {
     // After revealing we have to have one empty flag left
     assert_eq!(battlefield.flags_left, 2);
 }
+
+#[test]
+fn should_create_the_same_field_from_the_same_seed() {
+    let first = BattleField::with_seed(10, 10, 10, 42);
+    let second = BattleField::with_seed(10, 10, 10, 42);
+
+    assert_eq!(first.get_all(), second.get_all());
+}
+
+#[test]
+fn should_return_only_existing_neighbours_of_the_corner_cell() {
+    let battlefield = BattleField::new(3, 3, 0);
+
+    let mut neighbours_id = battlefield
+        .neighbours(CellPosition { x: 0, y: 0 })
+        .iter()
+        .map(|cell| cell.id)
+        .collect::<Vec<CellId>>();
+    neighbours_id.sort_unstable();
+
+    assert_eq!(neighbours_id, vec![1, 3, 4]);
+}
+
+#[test]
+fn should_chord_the_cell_when_all_bombs_around_are_flagged() {
+    // |1, 1, 0|
+    // |b, 1, 0|
+    // |1, 1, 0|
+    let map = vec![
+        vec![
+            Cell::new(0, CellType::Empty(1), CellPosition { x: 0, y: 0 }),
+            Cell::new(1, CellType::Mine(1), CellPosition { x: 0, y: 1 }),
+            Cell::new(2, CellType::Empty(1), CellPosition { x: 0, y: 2 }),
+        ],
+        vec![
+            Cell::new(3, CellType::Empty(1), CellPosition { x: 1, y: 0 }),
+            Cell::new(4, CellType::Empty(1), CellPosition { x: 1, y: 1 }),
+            Cell::new(5, CellType::Empty(1), CellPosition { x: 1, y: 2 }),
+        ],
+        vec![
+            Cell::new(6, CellType::Empty(0), CellPosition { x: 2, y: 0 }),
+            Cell::new(7, CellType::Empty(0), CellPosition { x: 2, y: 1 }),
+            Cell::new(8, CellType::Empty(0), CellPosition { x: 2, y: 2 }),
+        ],
+    ];
+    let mut battlefield = BattleField::with_map(map);
+
+    battlefield.reveal(4);
+    battlefield.flag(1);
+
+    let revealed = battlefield.chord(4);
+    let mut revealed_cells_id = revealed
+        .cells
+        .iter()
+        .map(|cell| cell.id)
+        .collect::<Vec<CellId>>();
+    revealed_cells_id.sort_unstable();
+
+    assert!(!revealed.game_is_over);
+    assert_eq!(revealed_cells_id, vec![0, 2, 3, 5, 6, 7, 8]);
+}
+
+#[test]
+fn should_not_chord_the_cell_if_flags_do_not_match_its_value() {
+    // |1, 1, 0|
+    // |b, 1, 0|
+    // |1, 1, 0|
+    let map = vec![
+        vec![
+            Cell::new(0, CellType::Empty(1), CellPosition { x: 0, y: 0 }),
+            Cell::new(1, CellType::Mine(1), CellPosition { x: 0, y: 1 }),
+            Cell::new(2, CellType::Empty(1), CellPosition { x: 0, y: 2 }),
+        ],
+        vec![
+            Cell::new(3, CellType::Empty(1), CellPosition { x: 1, y: 0 }),
+            Cell::new(4, CellType::Empty(1), CellPosition { x: 1, y: 1 }),
+            Cell::new(5, CellType::Empty(1), CellPosition { x: 1, y: 2 }),
+        ],
+        vec![
+            Cell::new(6, CellType::Empty(0), CellPosition { x: 2, y: 0 }),
+            Cell::new(7, CellType::Empty(0), CellPosition { x: 2, y: 1 }),
+            Cell::new(8, CellType::Empty(0), CellPosition { x: 2, y: 2 }),
+        ],
+    ];
+    let mut battlefield = BattleField::with_map(map);
+
+    battlefield.reveal(4);
+
+    let revealed = battlefield.chord(4);
+
+    assert!(!revealed.game_is_over);
+    assert!(revealed.cells.is_empty());
+}
+
+#[test]
+fn should_reveal_all_cells_if_chord_hits_the_bomb() {
+    // |1, 1, 0|
+    // |b, 1, 0|
+    // |1, 1, 0|
+    let map = vec![
+        vec![
+            Cell::new(0, CellType::Empty(1), CellPosition { x: 0, y: 0 }),
+            Cell::new(1, CellType::Mine(1), CellPosition { x: 0, y: 1 }),
+            Cell::new(2, CellType::Empty(1), CellPosition { x: 0, y: 2 }),
+        ],
+        vec![
+            Cell::new(3, CellType::Empty(1), CellPosition { x: 1, y: 0 }),
+            Cell::new(4, CellType::Empty(1), CellPosition { x: 1, y: 1 }),
+            Cell::new(5, CellType::Empty(1), CellPosition { x: 1, y: 2 }),
+        ],
+        vec![
+            Cell::new(6, CellType::Empty(0), CellPosition { x: 2, y: 0 }),
+            Cell::new(7, CellType::Empty(0), CellPosition { x: 2, y: 1 }),
+            Cell::new(8, CellType::Empty(0), CellPosition { x: 2, y: 2 }),
+        ],
+    ];
+    let mut battlefield = BattleField::with_map(map);
+
+    battlefield.reveal(4);
+
+    // Wrong flag
+    battlefield.flag(3);
+
+    let revealed = battlefield.chord(4);
+
+    assert!(revealed.game_is_over);
+}
+
+#[test]
+fn should_calculate_three_bv_of_the_map() {
+    // |0, 1, 1|
+    // |0, 1, b|
+    // |0, 1, 1|
+    let map = vec![
+        vec![
+            Cell::new(0, CellType::Empty(0), CellPosition { x: 0, y: 0 }),
+            Cell::new(1, CellType::Empty(0), CellPosition { x: 0, y: 1 }),
+            Cell::new(2, CellType::Empty(0), CellPosition { x: 0, y: 2 }),
+        ],
+        vec![
+            Cell::new(3, CellType::Empty(1), CellPosition { x: 1, y: 0 }),
+            Cell::new(4, CellType::Empty(1), CellPosition { x: 1, y: 1 }),
+            Cell::new(5, CellType::Empty(1), CellPosition { x: 1, y: 2 }),
+        ],
+        vec![
+            Cell::new(6, CellType::Empty(1), CellPosition { x: 2, y: 0 }),
+            Cell::new(7, CellType::Mine(1), CellPosition { x: 2, y: 1 }),
+            Cell::new(8, CellType::Empty(1), CellPosition { x: 2, y: 2 }),
+        ],
+    ];
+    let battlefield = BattleField::with_map(map);
+
+    // One opening on the left side and two
+    //  separate cells on the right side
+    assert_eq!(battlefield.three_bv(), 3);
+}
+
+#[test]
+fn should_hide_all_cells_on_restart() {
+    let mut battlefield = BattleField::with_seed(5, 5, 3, 7);
+    let initial_map = battlefield.get_all().clone();
+
+    battlefield.flag(0);
+    battlefield.reveal(1);
+    battlefield.restart();
+
+    assert_eq!(battlefield.get_all(), &initial_map);
+    assert_eq!(battlefield.flags_left, 3);
+}
+
+#[test]
+fn should_have_6_neighbours_on_hex_map() {
+    let config = GameConfig::new(5, 5, 0).with_tiling(Tiling::Hex);
+    let battlefield = BattleField::from_config(&config);
+
+    let mut neighbours = battlefield
+        .neighbours(CellPosition { x: 2, y: 2 })
+        .iter()
+        .map(|cell| (cell.position.x, cell.position.y))
+        .collect::<Vec<_>>();
+    neighbours.sort_unstable();
+
+    assert_eq!(
+        neighbours,
+        vec![(1, 2), (1, 3), (2, 1), (2, 3), (3, 1), (3, 2)]
+    );
+    assert_eq!(battlefield.neighbours(CellPosition { x: 0, y: 0 }).len(), 2);
+}
+
+#[test]
+fn should_count_only_hex_neighbours() {
+    let config = GameConfig::new(6, 6, 8)
+        .with_seed(3)
+        .with_tiling(Tiling::Hex);
+    let battlefield = BattleField::from_config(&config);
+
+    assert_values(&battlefield);
+}
+
+#[test]
+fn should_flood_fill_hex_map_through_hex_neighbours() {
+    // The only mine is at (1, 1): on a square map the opposite
+    //  corners (0, 0) and (2, 2) would touch it, but on
+    //  a hex map they aren't its neighbours
+    let mut map = vec![];
+    for x in 0..3 {
+        let mut col = vec![];
+        for y in 0..3 {
+            col.push(Cell::new(
+                x * 3 + y,
+                CellType::Empty(0),
+                CellPosition {
+                    x: x as i16,
+                    y: y as i16,
+                },
+            ));
+        }
+        map.push(col);
+    }
+    map[1][1].ctype = CellType::Mine(1);
+    for (x, y) in [(2, 1), (2, 0), (1, 0), (0, 1), (0, 2), (1, 2)].iter() {
+        map[*x][*y].ctype = CellType::Empty(1);
+    }
+
+    let mut battlefield = BattleField::with_map(map);
+    battlefield.topology = Grid::new(&GameConfig::new(3, 3, 0).with_tiling(Tiling::Hex));
+
+    let reveal = battlefield.reveal(0);
+
+    assert_eq!(
+        reveal.cells.iter().map(|cell| cell.id).collect::<Vec<_>>(),
+        vec![0, 3, 1]
+    );
+}
+
+#[test]
+fn should_have_neighbours_on_opposite_edges_of_wrapped_map() {
+    let config = GameConfig::new(5, 4, 0).with_wrap(true);
+    let battlefield = BattleField::from_config(&config);
+
+    let mut neighbours = battlefield
+        .neighbours(CellPosition { x: 0, y: 0 })
+        .iter()
+        .map(|cell| (cell.position.x, cell.position.y))
+        .collect::<Vec<_>>();
+    neighbours.sort_unstable();
+
+    assert_eq!(
+        neighbours,
+        vec![
+            (0, 1),
+            (0, 4),
+            (1, 0),
+            (1, 1),
+            (1, 4),
+            (3, 0),
+            (3, 1),
+            (3, 4)
+        ]
+    );
+}
+
+#[test]
+fn should_not_duplicate_neighbours_of_tiny_wrapped_map() {
+    let config = GameConfig::new(2, 2, 0).with_wrap(true);
+    let battlefield = BattleField::from_config(&config);
+
+    assert_eq!(battlefield.neighbours(CellPosition { x: 0, y: 0 }).len(), 3);
+}
+
+#[test]
+fn should_count_mines_across_edges_of_wrapped_map() {
+    let config = GameConfig::new(6, 6, 8).with_seed(5).with_wrap(true);
+    let battlefield = BattleField::from_config(&config);
+
+    assert_values(&battlefield);
+}
+
+#[test]
+fn should_count_mines_of_every_neighbourhood() {
+    let neighbourhoods = [
+        Neighbourhood::VonNeumann,
+        Neighbourhood::Knight,
+        Neighbourhood::Radius2,
+        Neighbourhood::Custom(vec![(0, -1), (0, -2), (3, 3)]),
+    ];
+
+    for neighbourhood in neighbourhoods.iter() {
+        let config = GameConfig::new(8, 8, 12)
+            .with_seed(9)
+            .with_neighbourhood(neighbourhood.clone());
+        let battlefield = BattleField::from_config(&config);
+
+        assert_eq!(battlefield.topology().neighbourhood(), neighbourhood);
+
+        assert_values(&battlefield);
+    }
+}
+
+#[test]
+fn should_count_far_custom_neighbours_on_the_edge_of_the_largest_map() {
+    use crate::engine::MAX_CUSTOM_OFFSET;
+
+    let neighbourhood = Neighbourhood::custom(vec![(MAX_CUSTOM_OFFSET, 0), (-1, 0)]).unwrap();
+
+    let last = CellPosition {
+        x: i16::MAX - 1,
+        y: 0,
+    };
+    let expected = [
+        (false, vec![i16::MAX - 2]),
+        (true, vec![MAX_CUSTOM_OFFSET - 1, i16::MAX - 2]),
+    ];
+
+    for (wrap, neighbours) in expected.iter() {
+        let config = GameConfig::new(1, i16::MAX as usize, 3000)
+            .with_seed(3)
+            .with_wrap(*wrap)
+            .with_neighbourhood(neighbourhood.clone());
+        let battlefield = BattleField::from_config(&config);
+
+        let columns = battlefield
+            .neighbours(last)
+            .iter()
+            .map(|cell| cell.position.x)
+            .collect::<Vec<i16>>();
+
+        assert_eq!(&columns, neighbours);
+        assert_values(&battlefield);
+    }
+}
+
+#[test]
+fn should_flood_fill_only_through_von_neumann_neighbours() {
+    let config = GameConfig::new(3, 3, 0).with_neighbourhood(Neighbourhood::VonNeumann);
+    let mut battlefield = BattleField::from_config(&config);
+
+    // The centre is a mine which splits the map into corners
+    //  that touch each other only diagonally
+    battlefield.get_mut(4).ctype = CellType::Mine(1);
+    for cell_id in [1, 3, 5, 7].iter() {
+        battlefield.get_mut(*cell_id).ctype = CellType::Empty(1);
+    }
+
+    let reveal = battlefield.reveal(0);
+
+    assert_eq!(
+        reveal.cells.iter().map(|cell| cell.id).collect::<Vec<_>>(),
+        vec![0, 1, 3]
+    );
+}
+
+#[test]
+fn should_have_26_neighbours_in_the_middle_of_3d_map() {
+    let config = GameConfig::new(3, 3, 0).with_layers(3);
+    let battlefield = BattleField::from_config(&config);
+    let centre = battlefield
+        .topology()
+        .position(CellCoordinates { x: 1, y: 1, z: 1 });
+
+    assert_eq!(battlefield.cells_count(), 27);
+    assert_eq!(battlefield.neighbours(centre).len(), 26);
+    assert_eq!(battlefield.neighbours(CellPosition { x: 0, y: 0 }).len(), 7);
+}
+
+#[test]
+fn should_validate_the_size_of_the_battlefield() {
+    let max = i16::MAX as usize;
+
+    assert!(GameConfig::new(255, 257, 0).validate().is_ok());
+    assert!(GameConfig::new(1, 2, 0).with_layers(max).validate().is_ok());
+    assert!(GameConfig::new(1, max, 0).validate().is_ok());
+
+    assert!(GameConfig::new(256, 256, 0).validate().is_err());
+    assert!(GameConfig::new(50, 50, 10)
+        .with_layers(30)
+        .validate()
+        .is_err());
+    assert!(GameConfig::new(200, 1, 0)
+        .with_layers(200)
+        .validate()
+        .is_err());
+    assert!(GameConfig::new(1, max + 1, 0).validate().is_err());
+    assert!(GameConfig::new(usize::MAX, 2, 0).validate().is_err());
+}
+
+#[test]
+fn should_create_battlefields_of_the_largest_size() {
+    use crate::engine::MAX_CELLS;
+
+    let battlefield = BattleField::from_config(&GameConfig::new(255, 257, 10));
+    let last = CellPosition { x: 256, y: 254 };
+
+    assert_eq!(battlefield.cells_count(), MAX_CELLS);
+    assert_eq!(battlefield.get_by_position(last).unwrap().id, 65534);
+
+    let config = GameConfig::new(1, 2, 10).with_layers(i16::MAX as usize);
+    let battlefield = BattleField::from_config(&config);
+    let last = CellCoordinates {
+        x: 1,
+        y: 0,
+        z: i16::MAX - 1,
+    };
+
+    assert_eq!(battlefield.get_by_coordinates(last).unwrap().id, 65533);
+    assert_values(&battlefield);
+}
+
+#[test]
+fn should_not_count_cells_of_the_next_layer_as_neighbours_in_the_layer() {
+    let config = GameConfig::new(3, 3, 0).with_layers(2);
+    let battlefield = BattleField::from_config(&config);
+
+    // The bottom row of the first layer and the top row
+    //  of the second one are placed one after another
+    let bottom = battlefield
+        .topology()
+        .position(CellCoordinates { x: 0, y: 2, z: 0 });
+    let neighbours = battlefield
+        .neighbours(bottom)
+        .iter()
+        .map(|cell| battlefield.topology().coordinates(cell.position))
+        .collect::<Vec<_>>();
+
+    assert_eq!(neighbours.len(), 7);
+    assert!(neighbours
+        .iter()
+        .all(|coordinates| coordinates.y >= 1 && coordinates.x <= 1));
+}
+
+#[test]
+fn should_count_mines_and_flood_fill_across_layers() {
+    let config = GameConfig::new(4, 4, 6).with_seed(1).with_layers(3);
+    let mut battlefield = BattleField::from_config(&config);
+
+    assert_eq!(battlefield.rows(), 4);
+    assert_eq!(battlefield.layers(), 3);
+    assert_eq!(battlefield.layer(2).len(), 4);
+    assert_eq!(battlefield.layer(2)[0].len(), 4);
+    assert!(battlefield.layer(3).is_empty());
+
+    assert_values(&battlefield);
+
+    let opening = battlefield
+        .get_all()
+        .iter()
+        .flatten()
+        .find(|cell| cell.ctype == CellType::Empty(0))
+        .map(|cell| cell.id)
+        .unwrap();
+    let reveal = battlefield.reveal(opening);
+
+    assert!(!reveal.game_is_over);
+    assert!(reveal
+        .cells
+        .iter()
+        .map(|cell| battlefield.topology().coordinates(cell.position).z)
+        .any(|z| z
+            != battlefield
+                .topology()
+                .coordinates(reveal.cells[0].position)
+                .z));
+}
+
+#[test]
+fn should_not_place_mines_on_void_cells_and_count_them() {
+    let mask = Mask::from_ascii("##.##\n#####\n.###.\n..#..").unwrap();
+    let config = GameConfig::new(0, 0, 6).with_seed(4).with_mask(mask);
+    let battlefield = BattleField::from_config(&config);
+
+    assert_eq!((battlefield.rows(), battlefield.cols()), (4, 5));
+    assert_eq!(battlefield.void_cells_count(), 7);
+
+    for cell in battlefield.get_all().iter().flatten() {
+        let is_void = cell.state == CellState::Void;
+
+        assert!(!(is_void && cell.ctype.is_mine()));
+
+        for neighbour in battlefield.neighbours(cell.position) {
+            assert_ne!(neighbour.state, CellState::Void);
+        }
+    }
+}
+
+#[test]
+fn should_not_reveal_void_cells() {
+    let mask = Mask::from_ascii("#.#").unwrap();
+    let config = GameConfig::new(0, 0, 0).with_mask(mask);
+    let mut battlefield = BattleField::from_config(&config);
+
+    // The hole splits the map into two separate cells
+    assert!(battlefield.reveal(1).cells.is_empty());
+    assert_eq!(battlefield.reveal(0).cells.len(), 1);
+    assert_eq!(battlefield.get(1).state, CellState::Void);
+
+    battlefield.restart();
+
+    assert_eq!(battlefield.get(1).state, CellState::Void);
+}
+
+#[test]
+fn should_return_only_existing_cells_by_coordinates() {
+    let mask = Mask::from_ascii("#.#\n###").unwrap();
+    let config = GameConfig::new(0, 0, 0).with_mask(mask).with_layers(2);
+    let battlefield = BattleField::from_config(&config);
+    let at = |x, y, z| battlefield.existing_cell(CellCoordinates { x, y, z });
+
+    assert_eq!(at(2, 1, 1).unwrap().position, CellPosition { x: 2, y: 3 });
+    assert_eq!(
+        at(1, 0, 0),
+        Err("cell (row: 0, col: 1, layer: 0) doesn't exist".to_string())
+    );
+    assert_eq!(
+        at(0, 2, 0),
+        Err("cell (row: 2, col: 0, layer: 0) is outside of the battlefield 2x3x2".to_string())
+    );
+    assert!(at(0, 0, 2).is_err());
+    assert!(at(-1, 0, 0).is_err());
+}
+
+#[test]
+fn should_place_several_mines_into_a_single_cell() {
+    let config = GameConfig::new(3, 3, 20)
+        .with_seed(5)
+        .with_max_mines_per_cell(3);
+    let battlefield = BattleField::from_config(&config);
+
+    let mines = battlefield
+        .get_all()
+        .iter()
+        .flatten()
+        .map(|cell| cell.ctype.mines() as u16)
+        .collect::<Vec<u16>>();
+
+    assert_eq!(mines.iter().sum::<u16>(), 20);
+    assert!(mines.iter().all(|mines| *mines <= 3));
+    assert!(battlefield.mined_cells_count() < 20);
+    assert_eq!(battlefield.config().max_mines_per_cell, 3);
+}
+
+#[test]
+fn should_bound_mines_per_cell_by_the_count_of_neighbours() {
+    let config = GameConfig::new(3, 3, 800)
+        .with_seed(1)
+        .with_max_mines_per_cell(200);
+    let battlefield = BattleField::from_config(&config);
+
+    assert_eq!(config.max_mines_per_cell, MAX_MINES_PER_CELL);
+    assert_eq!(battlefield.max_mines_per_cell(), 15);
+    assert_eq!(battlefield.bombs(), 9 * 15);
+    assert!(battlefield.validate().is_empty());
+
+    let config = GameConfig::new(5, 5, 100)
+        .with_seed(1)
+        .with_layers(3)
+        .with_neighbourhood(Neighbourhood::Radius2)
+        .with_max_mines_per_cell(4);
+
+    // Cells of the middle layer have up to 24 + 25 * 2 neighbours
+    assert_eq!(BattleField::from_config(&config).max_mines_per_cell(), 1);
+}
+
+#[test]
+fn should_count_every_mine_around_the_cell() {
+    let config = GameConfig::new(5, 5, 30)
+        .with_seed(2)
+        .with_max_mines_per_cell(4);
+    let battlefield = BattleField::from_config(&config);
+
+    assert_values(&battlefield);
+}
+
+#[test]
+fn should_cycle_flags_of_multi_mine_cells() {
+    let config = GameConfig::new(2, 2, 3).with_max_mines_per_cell(2);
+    let mut battlefield = BattleField::from_config(&config);
+
+    assert_eq!(battlefield.flag(0).state, CellState::Flagged(1));
+    assert_eq!(battlefield.flag(0).state, CellState::Flagged(2));
+    assert_eq!(battlefield.flags_left, 1);
+
+    assert_eq!(battlefield.flag(0).state, CellState::Hidden);
+    assert_eq!(battlefield.flags_left, 3);
+
+    battlefield.flag(0);
+    battlefield.flag(0);
+    battlefield.flag(1);
+
+    // There are no flags left, so the next click unflags the cell
+    assert_eq!(battlefield.flags_left, 0);
+    assert_eq!(battlefield.flag(1).state, CellState::Hidden);
+    assert_eq!(battlefield.flags_left, 1);
+}
+
+#[test]
+fn should_chord_by_the_count_of_flags() {
+    // |1, 2|
+    // |0, b|
+    let mut battlefield = BattleField::with_map(vec![
+        vec![
+            Cell::new(0, CellType::Empty(2), CellPosition { x: 0, y: 0 }),
+            Cell::new(1, CellType::Empty(2), CellPosition { x: 0, y: 1 }),
+        ],
+        vec![
+            Cell::new(2, CellType::Empty(2), CellPosition { x: 1, y: 0 }),
+            Cell::new(3, CellType::Mine(2), CellPosition { x: 1, y: 1 }),
+        ],
+    ]);
+
+    battlefield.reveal(0);
+    battlefield.flag(3);
+
+    assert!(battlefield.chord(0).cells.is_empty());
+
+    battlefield.flag(3);
+    let reveal = battlefield.chord(0);
+
+    assert!(!reveal.game_is_over);
+    assert_eq!(reveal.cells.len(), 2);
+}
+
+#[test]
+fn should_subtract_negative_mines_from_values() {
+    let config = GameConfig::new(6, 6, 6).with_seed(4).with_negative_bombs(5);
+    let battlefield = BattleField::from_config(&config);
+    let negative_mines = battlefield
+        .get_all()
+        .iter()
+        .flatten()
+        .filter(|cell| matches!(cell.ctype, CellType::NegativeMine(_)))
+        .count();
+
+    assert_eq!(negative_mines, 5);
+    assert_eq!(battlefield.mined_cells_count(), 11);
+    assert_eq!(battlefield.flags_left, 11);
+    assert_eq!(battlefield.config().negative_bombs, 5);
+
+    assert_values(&battlefield);
+}
+
+#[test]
+fn should_not_flood_fill_from_zero_with_mines_around() {
+    // |0, 0, 0, 0|
+    // |b, n, 0, 0|
+    let mut battlefield = BattleField::with_map(vec![
+        vec![
+            Cell::new(0, CellType::Empty(0), CellPosition { x: 0, y: 0 }),
+            Cell::new(1, CellType::Mine(1), CellPosition { x: 0, y: 1 }),
+        ],
+        vec![
+            Cell::new(2, CellType::Empty(0), CellPosition { x: 1, y: 0 }),
+            Cell::new(3, CellType::NegativeMine(1), CellPosition { x: 1, y: 1 }),
+        ],
+        vec![
+            Cell::new(4, CellType::Empty(-1), CellPosition { x: 2, y: 0 }),
+            Cell::new(5, CellType::Empty(-1), CellPosition { x: 2, y: 1 }),
+        ],
+        vec![
+            Cell::new(6, CellType::Empty(0), CellPosition { x: 3, y: 0 }),
+            Cell::new(7, CellType::Empty(0), CellPosition { x: 3, y: 1 }),
+        ],
+    ]);
+
+    assert_eq!(battlefield.reveal(0).cells.len(), 1);
+
+    let revealed = battlefield.reveal(6);
+    let mut revealed_ids = revealed
+        .cells
+        .iter()
+        .map(|cell| cell.id)
+        .collect::<Vec<_>>();
+    revealed_ids.sort_unstable();
+
+    assert_eq!(revealed_ids, vec![4, 5, 6, 7]);
+}
+
+#[test]
+fn should_place_negative_bombs_only_into_cells_left_free() {
+    let config = GameConfig::new(3, 3, 5).with_negative_bombs(5);
+    let battlefield = BattleField::from_config(&config);
+
+    assert_eq!(battlefield.bombs(), 5);
+    assert_eq!(battlefield.negative_bombs(), 4);
+    assert_eq!(battlefield.flags_left, 9);
+    assert_eq!(battlefield.mined_cells_count(), 9);
+    assert!(battlefield.validate().is_empty());
+}
+
+#[test]
+fn should_cycle_through_negative_flags() {
+    let config = GameConfig::new(2, 2, 1).with_negative_bombs(1);
+    let mut battlefield = BattleField::from_config(&config);
+
+    assert_eq!(battlefield.flag(0).state, CellState::Flagged(1));
+    assert_eq!(battlefield.flag(0).state, CellState::NegativeFlagged(1));
+    assert_eq!(battlefield.flags_left, 1);
+
+    assert_eq!(battlefield.flag(0).state, CellState::Hidden);
+    assert_eq!(battlefield.flags_left, 2);
+}
+
+#[test]
+fn should_chord_by_the_charge_of_flags() {
+    // |0, n|
+    // |b, 0|
+    let mut battlefield = BattleField::with_map(vec![
+        vec![
+            Cell::new(0, CellType::Empty(0), CellPosition { x: 0, y: 0 }),
+            Cell::new(1, CellType::Mine(1), CellPosition { x: 0, y: 1 }),
+        ],
+        vec![
+            Cell::new(2, CellType::NegativeMine(1), CellPosition { x: 1, y: 0 }),
+            Cell::new(3, CellType::Empty(0), CellPosition { x: 1, y: 1 }),
+        ],
+    ]);
+
+    battlefield.reveal(0);
+
+    // No flags around, so the cell can't be chorded
+    assert!(battlefield.chord(0).cells.is_empty());
+
+    battlefield.flag(1);
+
+    assert!(battlefield.chord(0).cells.is_empty());
+
+    battlefield.flag(2);
+    battlefield.flag(2);
+    let reveal = battlefield.chord(0);
+
+    assert!(!reveal.game_is_over);
+    assert_eq!(reveal.cells.len(), 1);
+    assert_eq!(reveal.cells[0].id, 3);
+}
+
+#[test]
+fn should_explode_the_mine_while_lives_are_left() {
+    // |0, 1, b|
+    // |0, 1, 1|
+    let config = GameConfig::new(2, 3, 0).with_lives(2);
+    let mut battlefield = BattleField::from_config(&config);
+    battlefield.get_mut(4).ctype = CellType::Mine(1);
+    battlefield.bombs = 1;
+    battlefield.flags_left = 1;
+
+    let reveal = battlefield.reveal(4);
+
+    assert!(!reveal.game_is_over);
+    assert_eq!(reveal.cells.len(), 1);
+    assert_eq!(reveal.cells[0].state, CellState::Exploded);
+    assert_eq!(battlefield.get(4).state, CellState::Exploded);
+    assert_eq!(battlefield.lives_left, 1);
+
+    // The exploded mine is flagged automatically
+    assert_eq!(battlefield.flags_left, 0);
+    assert!(battlefield.reveal(4).cells.is_empty());
+
+    battlefield.restart();
+
+    assert_eq!(battlefield.get(4).state, CellState::Hidden);
+    assert_eq!((battlefield.lives_left, battlefield.flags_left), (2, 1));
+}
+
+#[test]
+fn should_reveal_all_cells_when_the_last_life_is_taken() {
+    let config = GameConfig::new(4, 4, 2).with_seed(3).with_lives(2);
+    let mut battlefield = BattleField::from_config(&config);
+
+    let bombs = battlefield
+        .get_all()
+        .iter()
+        .flatten()
+        .filter(|cell| cell.ctype.is_mine())
+        .map(|cell| cell.id)
+        .collect::<Vec<CellId>>();
+
+    assert!(!battlefield.reveal(bombs[0]).game_is_over);
+
+    let reveal = battlefield.reveal(bombs[1]);
+
+    assert!(reveal.game_is_over);
+    assert_eq!(battlefield.lives_left, 0);
+    assert_eq!(battlefield.get(bombs[0]).state, CellState::Exploded);
+    assert!(battlefield
+        .get_all()
+        .iter()
+        .flatten()
+        .all(|cell| cell.state == CellState::Revealed || cell.state == CellState::Exploded));
+}
+
+#[test]
+fn should_count_exploded_mines_as_flags_on_chord() {
+    // |1, b|
+    let config = GameConfig::new(2, 1, 0).with_lives(3);
+    let mut battlefield = BattleField::from_config(&config);
+    battlefield.get_mut(0).ctype = CellType::Empty(1);
+    battlefield.get_mut(1).ctype = CellType::Mine(1);
+
+    battlefield.reveal(0);
+    battlefield.reveal(1);
+
+    assert_eq!(battlefield.config().lives, 3);
+    assert!(battlefield.chord(0).cells.is_empty());
+    assert_eq!(battlefield.lives_left, 2);
+}
+
+#[test]
+fn should_report_flags_and_mines_when_the_game_is_lost() {
+    // |b, b|
+    // |1, b|
+    // |2, 1|
+    let mut battlefield = BattleField::with_map(vec![
+        vec![
+            Cell::new(0, CellType::Mine(1), CellPosition { x: 0, y: 0 }),
+            Cell::new(1, CellType::Empty(2), CellPosition { x: 0, y: 1 }),
+            Cell::new(2, CellType::Empty(1), CellPosition { x: 0, y: 2 }),
+        ],
+        vec![
+            Cell::new(3, CellType::Mine(1), CellPosition { x: 1, y: 0 }),
+            Cell::new(4, CellType::Mine(1), CellPosition { x: 1, y: 1 }),
+            Cell::new(5, CellType::Empty(1), CellPosition { x: 1, y: 2 }),
+        ],
+    ]);
+
+    battlefield.flag(3);
+    battlefield.flag(2);
+
+    let reveal = battlefield.reveal(0);
+    let loss = reveal.loss.unwrap();
+    let ids = |cells: &[Cell]| cells.iter().map(|cell| cell.id).collect::<Vec<CellId>>();
+
+    assert!(reveal.game_is_over);
+    assert_eq!(loss.detonated.id, 0);
+    assert_eq!(loss.detonated.state, CellState::Exploded);
+    assert_eq!(ids(&loss.wrong_flags), vec![2]);
+    assert_eq!(ids(&loss.correct_flags), vec![3]);
+    assert_eq!(ids(&loss.unflagged_mines), vec![4]);
+
+    assert_eq!(battlefield.get(0).state, CellState::Exploded);
+    assert_eq!(battlefield.get(2).state, CellState::WrongFlag);
+    assert_eq!(battlefield.get(3).state, CellState::Flagged(1));
+    assert_eq!(battlefield.get(4).state, CellState::Revealed);
+}
+
+#[test]
+fn should_not_report_loss_while_lives_are_left() {
+    let config = GameConfig::new(4, 4, 2).with_seed(3).with_lives(2);
+    let mut battlefield = BattleField::from_config(&config);
+
+    let bomb = battlefield
+        .get_all()
+        .iter()
+        .flatten()
+        .find(|cell| cell.ctype.is_mine())
+        .map(|cell| cell.id)
+        .unwrap();
+
+    assert_eq!(battlefield.reveal(bomb).loss, None);
+}
+
+#[test]
+fn should_flag_all_mines_with_their_kinds_and_counts() {
+    // |b, n|
+    // |2, 0|
+    let mut battlefield = BattleField::with_map(vec![
+        vec![
+            Cell::new(0, CellType::Mine(2), CellPosition { x: 0, y: 0 }),
+            Cell::new(1, CellType::Empty(1), CellPosition { x: 0, y: 1 }),
+        ],
+        vec![
+            Cell::new(2, CellType::NegativeMine(1), CellPosition { x: 1, y: 0 }),
+            Cell::new(3, CellType::Empty(1), CellPosition { x: 1, y: 1 }),
+        ],
+    ]);
+
+    battlefield.flag(0);
+
+    let flagged = battlefield.flag_all_mines();
+
+    assert_eq!(
+        flagged.iter().map(|cell| cell.state).collect::<Vec<_>>(),
+        vec![CellState::Flagged(2), CellState::NegativeFlagged(1)]
+    );
+    assert_eq!(battlefield.flags_left, 0);
+    assert!(battlefield.flag_all_mines().is_empty());
+}
+
+#[test]
+fn should_check_that_all_mines_are_flagged_with_their_counts() {
+    // |b, 1|
+    // |b, 2|
+    let mut battlefield = BattleField::with_map(vec![
+        vec![
+            Cell::new(0, CellType::Mine(2), CellPosition { x: 0, y: 0 }),
+            Cell::new(1, CellType::Mine(1), CellPosition { x: 0, y: 1 }),
+        ],
+        vec![
+            Cell::new(2, CellType::Empty(3), CellPosition { x: 1, y: 0 }),
+            Cell::new(3, CellType::Empty(3), CellPosition { x: 1, y: 1 }),
+        ],
+    ]);
+
+    battlefield.get_mut(0).state = CellState::Flagged(1);
+    battlefield.get_mut(1).state = CellState::Exploded;
+
+    assert!(!battlefield.are_all_mines_flagged());
+
+    battlefield.get_mut(0).state = CellState::Flagged(2);
+    battlefield.get_mut(2).state = CellState::Flagged(1);
+
+    assert!(!battlefield.are_all_mines_flagged());
+
+    battlefield.get_mut(2).state = CellState::Hidden;
+
+    assert!(battlefield.are_all_mines_flagged());
+}
+
+#[test]
+fn should_keep_revealed_values_when_mines_are_relocated() {
+    for seed in 0..10 {
+        let mut battlefield = BattleField::with_seed(8, 8, 12, seed);
+
+        let opening = battlefield
+            .get_all()
+            .iter()
+            .flatten()
+            .find(|cell| cell.ctype == CellType::Empty(0))
+            .map(|cell| cell.id)
+            .unwrap();
+        battlefield.reveal(opening);
+
+        let revealed = battlefield
+            .get_all()
+            .iter()
+            .flatten()
+            .filter(|cell| cell.state == CellState::Revealed)
+            .copied()
+            .collect::<Vec<Cell>>();
+        let mine = battlefield
+            .get_all()
+            .iter()
+            .flatten()
+            .find(|cell| cell.ctype.is_mine())
+            .map(|cell| cell.id)
+            .unwrap();
+
+        if !battlefield.relocate_mines(mine, false) {
+            continue;
+        }
+
+        assert!(!battlefield.get(mine).ctype.is_mine());
+        assert_eq!(battlefield.mined_cells_count(), 12);
+
+        for cell in revealed {
+            assert_eq!(battlefield.get(cell.id).ctype, cell.ctype);
+        }
+
+        // Values of hidden cells are counted by new mines
+        assert_values(&battlefield);
+    }
+}
@@ -3,24 +3,70 @@ pub type CellId = u16;
 /// Should contain 2 structure
 ///  1. Factory - to create Minesweeper engine
 ///  2. Engine which contain game state and provides
-///      some methods to uncover the cells
+///     some methods to uncover the cells
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum CellType {
-    /// Cell is a mine
-    Mine,
+    /// Cell holds provided count of mines,
+    ///  it's always `1` in the classic game
+    Mine(u8),
+
+    /// Cell holds provided count of negative mines which
+    ///  subtract one from clues around them for every mine
+    NegativeMine(u8),
 
     /// Cell is empty but it may be next to the bomb
     ///  if it's not it would contain `0`
-    Empty(u8),
+    ///
+    /// The value is the total count of mines around the cell
+    ///  minus the count of negative mines around it, so it may
+    ///  be `0` or negative even when mines are around
+    Empty(i8),
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+impl CellType {
+    /// Returns `true` if the cell holds at least one mine of any kind
+    pub fn is_mine(self) -> bool {
+        matches!(self, CellType::Mine(_) | CellType::NegativeMine(_))
+    }
+
+    /// Returns how many mines of any kind the cell holds
+    pub fn mines(self) -> u8 {
+        match self {
+            CellType::Mine(mines) | CellType::NegativeMine(mines) => mines,
+            CellType::Empty(_) => 0,
+        }
+    }
+
+    /// Returns how much the cell adds to values of cells around it,
+    ///  it's wider than values so sums of charges never overflow
+    pub fn charge(self) -> i16 {
+        match self {
+            CellType::Mine(mines) => mines as i16,
+            CellType::NegativeMine(mines) => -(mines as i16),
+            CellType::Empty(_) => 0,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct CellPosition {
     pub x: i16,
     pub y: i16,
 }
 
+/// Position of the cell on the map with several layers
+///
+/// Layers are stacked along the `y` axis of `CellPosition`,
+///  so the cell `(x, y, z)` has the position `(x, z * rows + y)`
+///  and positions of 2D maps are the same as coordinates with `z = 0`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CellCoordinates {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum CellState {
     /// Default cell status
@@ -29,8 +75,56 @@ pub enum CellState {
     /// Cell was uncovered
     Revealed,
 
-    /// Cell was flagged as a potential bomb
-    Flagged,
+    /// Cell was flagged as a potential bomb with provided count
+    ///  of flags, it's always `1` in the classic game
+    Flagged(u8),
+
+    /// Cell was flagged as a potential negative bomb
+    ///  with provided count of flags
+    NegativeFlagged(u8),
+
+    /// Cell is a mine which has been hit by the player,
+    ///  it's flagged automatically while lives are left
+    Exploded,
+
+    /// Cell was flagged but it doesn't contain such mines,
+    ///  it's shown when the game is lost
+    WrongFlag,
+
+    /// Cell doesn't exist on the battlefield of irregular shape,
+    ///  it can't be revealed or flagged
+    Void,
+}
+
+impl CellState {
+    /// Returns `true` if the cell has at least one flag of any kind
+    pub fn is_flagged(self) -> bool {
+        matches!(self, CellState::Flagged(_) | CellState::NegativeFlagged(_))
+    }
+
+    /// Returns `true` if the player hasn't opened the cell yet,
+    ///  so it's hidden or flagged
+    pub fn is_unopened(self) -> bool {
+        self == CellState::Hidden || self.is_flagged()
+    }
+
+    /// Returns how many flags of any kind the cell has
+    pub fn flags(self) -> u8 {
+        match self {
+            CellState::Flagged(flags) | CellState::NegativeFlagged(flags) => flags,
+            _ => 0,
+        }
+    }
+
+    /// Returns how much flags of the cell add to values
+    ///  of cells around it, the same way as mines do
+    pub fn charge(self) -> i16 {
+        match self {
+            CellState::Flagged(flags) => flags as i16,
+            CellState::NegativeFlagged(flags) => -(flags as i16),
+            _ => 0,
+        }
+    }
 }
 
 /// Cell represent each tile on the board
@@ -61,24 +155,120 @@ impl Cell {
         }
     }
 
-    /// Reveal the cell
+    /// Returns how many flags the cell has,
+    ///  exploded mines are flagged automatically
+    pub fn flags(&self) -> u8 {
+        match self.state {
+            CellState::Exploded => self.ctype.mines(),
+            state => state.flags(),
+        }
+    }
+
+    /// Returns how much flags of the cell add to values
+    ///  of cells around it including automatic flags
+    ///  of exploded mines
+    pub fn flag_charge(&self) -> i16 {
+        match self.state {
+            CellState::Exploded => self.ctype.charge(),
+            state => state.charge(),
+        }
+    }
+
+    /// Reveal the cell if it exists
     pub fn reveal(&mut self) {
-        self.state = CellState::Revealed;
+        if self.state != CellState::Void {
+            self.state = CellState::Revealed;
+        }
     }
 
     /// Mark the cell as a flag if it's not (returns `true`)
     ///  or unmark it as a flag if it's flagged (returns `false`)
     pub fn flag(&mut self) -> bool {
-        if self.state == CellState::Flagged {
-            self.state = CellState::Hidden;
+        self.flag_up_to(1)
+    }
+
+    /// Adds one more flag to the cell (returns `true`) or removes
+    ///  all flags if it has already `max_flags` flags (returns `false`),
+    ///  so flags cycle through `1..=max_flags`
+    pub fn flag_up_to(&mut self, max_flags: u8) -> bool {
+        match self.state {
+            CellState::Hidden => {
+                self.state = CellState::Flagged(1);
 
-            false
-        } else if self.state == CellState::Hidden {
-            self.state = CellState::Flagged;
+                true
+            }
+            CellState::Flagged(flags) if flags < max_flags => {
+                self.state = CellState::Flagged(flags + 1);
+
+                true
+            }
+            CellState::Flagged(_) | CellState::NegativeFlagged(_) => {
+                self.state = CellState::Hidden;
+
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Cycles flags the same way as `flag_up_to` does but goes
+    ///  through negative flags after `max_flags` flags:
+    ///  `1..=max_flags` flags, then `1..=max_flags` negative flags
+    ///
+    /// Returns `false` only if all flags were removed
+    pub fn flag_with_negative_up_to(&mut self, max_flags: u8) -> bool {
+        match self.state {
+            CellState::Flagged(flags) if flags >= max_flags => {
+                self.state = CellState::NegativeFlagged(1);
+
+                true
+            }
+            CellState::NegativeFlagged(flags) if flags < max_flags => {
+                self.state = CellState::NegativeFlagged(flags + 1);
+
+                true
+            }
+            _ => self.flag_up_to(max_flags),
+        }
+    }
+}
+
+/// The cell as the player sees it
+///
+/// The type of the cell is known only if the cell has been revealed
+///  or the game has ended, so the client can't find out where
+///  bombs are placed by looking into the data
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PlayerCell {
+    /// Cell identificator
+    pub id: CellId,
+
+    /// Cell type if the player is allowed to know it
+    pub ctype: Option<CellType>,
+
+    pub state: CellState,
+
+    /// Cell position
+    pub position: CellPosition,
+}
+
+impl Cell {
+    /// Returns the cell as the player sees it
+    ///
+    /// If `is_game_over` is `true` types of all cells are exposed
+    pub fn player_view(&self, is_game_over: bool) -> PlayerCell {
+        let is_type_visible =
+            is_game_over || self.state == CellState::Revealed || self.state == CellState::Exploded;
 
-            true
-        } else {
-            false
+        PlayerCell {
+            id: self.id,
+            ctype: if is_type_visible {
+                Some(self.ctype)
+            } else {
+                None
+            },
+            state: self.state,
+            position: self.position,
         }
     }
 }
@@ -0,0 +1,248 @@
+use crate::engine::mask::Mask;
+use crate::engine::topology::{Neighbourhood, Tiling};
+
+/// How many mines a single cell may contain at most,
+///  so counts of mines and flags fit `i8` the same as values
+pub const MAX_MINES_PER_CELL: u8 = i8::MAX as u8;
+
+/// How many cells the battlefield may have at most,
+///  so every cell gets its own `CellId`
+pub const MAX_CELLS: usize = u16::MAX as usize;
+
+/// Decides when the game is won
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum WinRule {
+    /// The game is won when all cells without mines are revealed
+    #[default]
+    RevealAll,
+
+    /// The game is also won when every mine is flagged correctly
+    ///  and there are no wrong flags
+    RevealOrFlagAll,
+}
+
+/// Decides what happens to mines when the player reveals a cell
+///  which can't be proved safe
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum GuessMode {
+    /// Mines stay where they have been placed
+    #[default]
+    Normal,
+
+    /// If no cell can be proved safe, the guessed cell is made safe
+    ///  by moving mines to another layout consistent with all clues
+    ///
+    /// Only single mines can be moved, so boards with several mines
+    ///  per cell or negative mines are rejected in this mode
+    Kind,
+
+    /// If any layout consistent with all clues has a mine in the guessed
+    ///  cell, mines are moved to it, so only cells which are proved safe
+    ///  are safe to reveal
+    ///
+    /// The first reveal isn't touched, otherwise no game could be won
+    ///
+    /// The same as `Kind` it's rejected for boards with several mines
+    ///  per cell or negative mines
+    Cruel,
+}
+
+/// Settings of the new game
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameConfig {
+    pub rows: usize,
+    pub cols: usize,
+
+    /// How many mines are placed, if cells can't take all of them
+    ///  only as many as they can take are placed
+    pub bombs: u16,
+
+    /// How many negative mines are placed in addition to `bombs`,
+    ///  `0` for the ordinary game
+    ///
+    /// They never share cells with `bombs`, so only as many
+    ///  as cells left free by `bombs` can take are placed
+    pub negative_bombs: u16,
+
+    /// How many layers of `rows x cols` the map has,
+    ///  `1` for the ordinary flat map
+    pub layers: usize,
+
+    /// If provided bombs are placed based on the seed,
+    ///  so the same seed always produces the same map
+    pub seed: Option<u64>,
+
+    /// The shape of cells
+    pub tiling: Tiling,
+
+    /// Cells which are counted by values of empty cells
+    pub neighbourhood: Neighbourhood,
+
+    /// If `true` opposite edges of the map are neighbours,
+    ///  so the map becomes a torus
+    pub wrap: bool,
+
+    /// The shape of the map, every layer is a full rectangle if it's `None`
+    pub mask: Option<Mask>,
+
+    /// How many mines a single cell may contain,
+    ///  `1` for the ordinary game
+    ///
+    /// It's at most `MAX_MINES_PER_CELL` and the battlefield lowers it
+    ///  further if cells have so many neighbours that values
+    ///  wouldn't fit `i8`, e.g. to `15` for 8 neighbours
+    pub max_mines_per_cell: u8,
+
+    /// How many mines the player may hit before the game is lost,
+    ///  `1` for the ordinary game
+    pub lives: u8,
+
+    /// When the game is won, `WinRule::RevealAll` for the ordinary game
+    pub win_rule: WinRule,
+
+    /// What happens to mines when the player guesses,
+    ///  `GuessMode::Normal` for the ordinary game
+    pub guess_mode: GuessMode,
+}
+
+impl GameConfig {
+    /// Creates the config of the random battlefield
+    pub fn new(rows: usize, cols: usize, bombs: u16) -> Self {
+        Self {
+            rows,
+            cols,
+            bombs,
+            negative_bombs: 0,
+            layers: 1,
+            seed: None,
+            tiling: Tiling::Square,
+            neighbourhood: Neighbourhood::Moore,
+            wrap: false,
+            mask: None,
+            max_mines_per_cell: 1,
+            lives: 1,
+            win_rule: WinRule::RevealAll,
+            guess_mode: GuessMode::Normal,
+        }
+    }
+
+    /// Returns the same config but with provided `seed`
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            ..self
+        }
+    }
+
+    /// Returns the same config but with provided `tiling`
+    pub fn with_tiling(self, tiling: Tiling) -> Self {
+        Self { tiling, ..self }
+    }
+
+    /// Returns the same config but with wrapped or not wrapped edges
+    pub fn with_wrap(self, wrap: bool) -> Self {
+        Self { wrap, ..self }
+    }
+
+    /// Returns the same config but with provided `neighbourhood`
+    pub fn with_neighbourhood(self, neighbourhood: Neighbourhood) -> Self {
+        Self {
+            neighbourhood,
+            ..self
+        }
+    }
+
+    /// Returns the same config but with provided count of `layers`
+    pub fn with_layers(self, layers: usize) -> Self {
+        Self { layers, ..self }
+    }
+
+    /// Returns the same config but with the map of the `mask` shape,
+    ///  rows and columns are taken from the mask
+    pub fn with_mask(self, mask: Mask) -> Self {
+        Self {
+            rows: mask.rows(),
+            cols: mask.cols(),
+            mask: Some(mask),
+            ..self
+        }
+    }
+
+    /// Returns the same config but with cells which may
+    ///  contain up to `max_mines_per_cell` mines,
+    ///  it's clamped to `1..=MAX_MINES_PER_CELL`
+    pub fn with_max_mines_per_cell(self, max_mines_per_cell: u8) -> Self {
+        Self {
+            max_mines_per_cell: max_mines_per_cell.clamp(1, MAX_MINES_PER_CELL),
+            ..self
+        }
+    }
+
+    /// Returns the same config but with provided count
+    ///  of negative mines
+    pub fn with_negative_bombs(self, negative_bombs: u16) -> Self {
+        Self {
+            negative_bombs,
+            ..self
+        }
+    }
+
+    /// Returns the same config but with provided count of `lives`
+    pub fn with_lives(self, lives: u8) -> Self {
+        Self { lives, ..self }
+    }
+
+    /// Returns the same config but with provided `win_rule`
+    pub fn with_win_rule(self, win_rule: WinRule) -> Self {
+        Self { win_rule, ..self }
+    }
+
+    /// Returns the same config but with provided `guess_mode`
+    pub fn with_guess_mode(self, guess_mode: GuessMode) -> Self {
+        Self { guess_mode, ..self }
+    }
+
+    /// Returns the error if the battlefield can't be built by the config:
+    ///  it has more than `MAX_CELLS` cells, columns and rows of all
+    ///  layers don't fit `i16` positions of cells or the guess mode
+    ///  can't move its mines
+    pub fn validate(&self) -> Result<(), String> {
+        let layers = self.layers.max(1);
+        let height = self.rows.saturating_mul(layers);
+        let cells = height.saturating_mul(self.cols);
+
+        if cells > MAX_CELLS {
+            return Err(format!(
+                "battlefield {}x{}x{} has more than {} cells",
+                self.rows, self.cols, layers, MAX_CELLS
+            ));
+        }
+
+        let max = i16::MAX as usize;
+        if self.cols > max || height > max {
+            return Err(format!(
+                "battlefield {}x{}x{} has more than {} columns or rows of all layers",
+                self.rows, self.cols, layers, max
+            ));
+        }
+
+        self.guess_mode
+            .check(self.max_mines_per_cell, self.negative_bombs)
+    }
+}
+
+impl GuessMode {
+    /// Returns the error if the mode can't move mines of the battlefield
+    ///  which has up to `max_mines_per_cell` mines per cell
+    ///  and `negative_bombs` negative mines
+    pub(crate) fn check(self, max_mines_per_cell: u8, negative_bombs: u16) -> Result<(), String> {
+        if self != GuessMode::Normal && (max_mines_per_cell > 1 || negative_bombs > 0) {
+            return Err(format!(
+                "{:?} guess mode needs the battlefield with single mines and without negative mines",
+                self
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,382 @@
+use std::collections::HashSet;
+
+#[cfg(test)]
+use crate::engine::battlefield::assert_values;
+use crate::engine::battlefield::BattleField;
+use crate::engine::cell::*;
+use crate::engine::config::{GameConfig, GuessMode, WinRule};
+use crate::engine::game::Game;
+use crate::engine::grid::Grid;
+use crate::engine::topology::Topology;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+/// Editor of the battlefield for building puzzle boards by hand
+///
+/// Every change of mines recounts values of empty cells,
+///  so the battlefield is always ready to be played
+pub struct BoardEditor<T: Topology = Grid> {
+    battlefield: BattleField<T>,
+    win_rule: WinRule,
+    guess_mode: GuessMode,
+}
+
+/// Public interface for editors of built-in battlefields
+impl BoardEditor {
+    /// Creates the editor of the battlefield without mines
+    ///  which has the shape described by provided config
+    ///
+    /// Mines of the config are ignored, its rules are kept
+    ///  for games on the edited board
+    ///
+    /// Panics if the config is invalid, see `GameConfig::validate`
+    pub fn new(config: &GameConfig) -> Self {
+        let empty = GameConfig {
+            bombs: 0,
+            negative_bombs: 0,
+            ..config.clone()
+        };
+
+        Self {
+            win_rule: config.win_rule,
+            guess_mode: config.guess_mode,
+            ..Self::from_battlefield(BattleField::from_config(&empty))
+        }
+    }
+}
+
+/// Public interface for editors of battlefields of any geometry
+impl<T: Topology> BoardEditor<T> {
+    /// Creates the editor of already existing battlefield,
+    ///  all cells are hidden again
+    pub fn from_battlefield(mut battlefield: BattleField<T>) -> Self {
+        battlefield.restart();
+
+        Self {
+            battlefield,
+            win_rule: WinRule::default(),
+            guess_mode: GuessMode::default(),
+        }
+    }
+
+    /// Puts the mine into the empty cell on provided `position`
+    ///  or removes all mines from the mined one
+    ///  and returns the actual cell
+    pub fn toggle_mine(&mut self, position: CellPosition) -> Result<&Cell, String> {
+        let mines = if self.existing_cell(position)?.ctype.is_mine() {
+            0
+        } else {
+            1
+        };
+        self.battlefield.set_mines(&[(position, mines)]);
+
+        self.existing_cell(position)
+    }
+
+    /// Replaces all mines by single mines in cells with provided `ids`,
+    ///  so the board saved by `mines` may be built again
+    ///
+    /// Nothing is changed if any of cells doesn't exist
+    pub fn set_mines(&mut self, ids: &[CellId]) -> Result<(), String> {
+        let cells = self.battlefield.get_all().iter().flatten();
+        let positions = ids
+            .iter()
+            .map(|id| match cells.clone().find(|cell| cell.id == *id) {
+                Some(cell) if cell.state != CellState::Void => Ok(cell.position),
+                Some(_) => Err(format!("cell {} doesn't exist", id)),
+                None => Err(format!("cell {} is outside of the battlefield", id)),
+            })
+            .collect::<Result<Vec<CellPosition>, String>>()?;
+
+        self.clear();
+        let mines = positions
+            .into_iter()
+            .map(|position| (position, 1))
+            .collect::<Vec<_>>();
+        self.battlefield.set_mines(&mines);
+
+        Ok(())
+    }
+
+    /// Removes all mines from the battlefield
+    pub fn clear(&mut self) {
+        let mines = self
+            .battlefield
+            .get_all()
+            .iter()
+            .flatten()
+            .filter(|cell| cell.ctype.is_mine())
+            .map(|cell| (cell.position, 0))
+            .collect::<Vec<_>>();
+
+        self.battlefield.set_mines(&mines);
+    }
+
+    /// Replaces mines of the rectangle between `from` and `to` positions
+    ///  including both of them by `bombs` mines in random cells
+    ///
+    /// If `seed` is provided the same seed always picks the same cells
+    pub fn randomize_region(
+        &mut self,
+        from: CellPosition,
+        to: CellPosition,
+        bombs: u16,
+        seed: Option<u64>,
+    ) -> Result<(), String> {
+        let (min_x, max_x) = (from.x.min(to.x), from.x.max(to.x));
+        let (min_y, max_y) = (from.y.min(to.y), from.y.max(to.y));
+
+        let region = self
+            .battlefield
+            .get_all()
+            .iter()
+            .flatten()
+            .filter(|cell| {
+                cell.state != CellState::Void
+                    && (min_x..=max_x).contains(&cell.position.x)
+                    && (min_y..=max_y).contains(&cell.position.y)
+            })
+            .map(|cell| cell.position)
+            .collect::<Vec<CellPosition>>();
+
+        if bombs as usize > region.len() {
+            return Err(format!(
+                "region has {} cells but {} mines are requested",
+                region.len(),
+                bombs
+            ));
+        }
+
+        let picked = match seed {
+            Some(seed) => rand::seq::index::sample(
+                &mut StdRng::seed_from_u64(seed),
+                region.len(),
+                bombs as usize,
+            ),
+            None => rand::seq::index::sample(&mut rand::thread_rng(), region.len(), bombs as usize),
+        }
+        .into_iter()
+        .collect::<HashSet<usize>>();
+
+        let mines = region
+            .iter()
+            .enumerate()
+            .map(|(index, position)| (*position, picked.contains(&index) as u8))
+            .collect::<Vec<_>>();
+        self.battlefield.set_mines(&mines);
+
+        Ok(())
+    }
+
+    /// Returns ids of all cells which contain mines,
+    ///  so the board may be saved and built again later
+    pub fn mines(&self) -> Vec<CellId> {
+        self.battlefield
+            .get_all()
+            .iter()
+            .flatten()
+            .filter(|cell| cell.ctype.is_mine())
+            .map(|cell| cell.id)
+            .collect()
+    }
+
+    /// Returns the battlefield which is being edited
+    pub fn battlefield(&self) -> &BattleField<T> {
+        &self.battlefield
+    }
+
+    /// Finishes editing and returns the battlefield
+    ///  which is ready to be played
+    pub fn into_battlefield(self) -> BattleField<T> {
+        self.battlefield
+    }
+
+    /// Creates the game on the copy of the edited battlefield
+    ///  with rules of the config the editor was created with
+    pub fn play(&self) -> Game<T>
+    where
+        T: Clone,
+    {
+        let mut game = Game::from_battlefield(self.battlefield.clone());
+        game.set_win_rule(self.win_rule);
+
+        // The guess mode has been checked with the battlefield
+        //  when the editor was created, so it's always accepted
+        let _ = game.set_guess_mode(self.guess_mode);
+
+        game
+    }
+
+    /// Returns the cell on provided `position`
+    ///  or the error if it doesn't exist
+    fn existing_cell(&self, position: CellPosition) -> Result<&Cell, String> {
+        match self.battlefield.get_by_position(position) {
+            Some(cell) if cell.state != CellState::Void => Ok(cell),
+            Some(_) => Err(format!(
+                "cell ({}, {}) doesn't exist",
+                position.x, position.y
+            )),
+            None => Err(format!(
+                "cell ({}, {}) is outside of the battlefield",
+                position.x, position.y
+            )),
+        }
+    }
+}
+
+#[test]
+fn should_create_the_board_without_mines() {
+    let editor = BoardEditor::new(&GameConfig::new(4, 5, 10));
+
+    assert!(editor.mines().is_empty());
+    assert_eq!(editor.battlefield().bombs(), 0);
+    assert_eq!(editor.battlefield().cells_count(), 20);
+}
+
+#[test]
+fn should_recount_values_when_the_mine_is_toggled() {
+    let mut editor = BoardEditor::new(&GameConfig::new(3, 3, 0));
+    let center = CellPosition { x: 1, y: 1 };
+
+    assert_eq!(editor.toggle_mine(center).unwrap().ctype, CellType::Mine(1));
+    assert_eq!(editor.battlefield().bombs(), 1);
+    assert_eq!(editor.battlefield().flags_left, 1);
+    assert!(editor
+        .battlefield()
+        .get_all()
+        .iter()
+        .flatten()
+        .all(|cell| cell.position == center || cell.ctype == CellType::Empty(1)));
+
+    assert_eq!(
+        editor.toggle_mine(center).unwrap().ctype,
+        CellType::Empty(0)
+    );
+    assert_eq!(editor.battlefield().bombs(), 0);
+    assert_values(editor.battlefield());
+}
+
+#[test]
+fn should_not_toggle_cells_which_do_not_exist() {
+    use crate::engine::Mask;
+
+    let mask = Mask::from_ascii("#.\n##").unwrap();
+    let mut editor = BoardEditor::new(&GameConfig::new(0, 0, 0).with_mask(mask));
+
+    assert!(editor.toggle_mine(CellPosition { x: 1, y: 0 }).is_err());
+    assert!(editor.toggle_mine(CellPosition { x: 5, y: 5 }).is_err());
+    assert!(editor.mines().is_empty());
+}
+
+#[test]
+fn should_clear_all_mines() {
+    let mut editor = BoardEditor::from_battlefield(BattleField::with_seed(6, 6, 10, 1));
+
+    editor.clear();
+
+    assert!(editor.mines().is_empty());
+    assert_eq!(editor.battlefield().bombs(), 0);
+    assert_values(editor.battlefield());
+}
+
+#[test]
+fn should_randomize_mines_only_inside_of_the_region() {
+    let mut editor = BoardEditor::new(&GameConfig::new(8, 8, 0));
+    let outside = CellPosition { x: 7, y: 7 };
+    editor.toggle_mine(outside).unwrap();
+
+    let (from, to) = (CellPosition { x: 4, y: 4 }, CellPosition { x: 1, y: 2 });
+    editor.randomize_region(from, to, 5, Some(3)).unwrap();
+
+    let mines = editor
+        .battlefield()
+        .get_all()
+        .iter()
+        .flatten()
+        .filter(|cell| cell.ctype.is_mine())
+        .map(|cell| cell.position)
+        .collect::<Vec<CellPosition>>();
+
+    assert_eq!(mines.len(), 6);
+    assert!(mines.contains(&outside));
+    assert!(mines
+        .iter()
+        .filter(|position| **position != outside)
+        .all(|position| (1..=4).contains(&position.x) && (2..=4).contains(&position.y)));
+    assert_eq!(editor.battlefield().bombs(), 6);
+    assert_values(editor.battlefield());
+
+    // The region has only 12 cells
+    assert!(editor.randomize_region(from, to, 13, None).is_err());
+}
+
+#[test]
+fn should_hide_cells_of_edited_battlefield() {
+    let mut battlefield = BattleField::with_seed(5, 5, 3, 2);
+    battlefield.flag(0);
+
+    let editor = BoardEditor::from_battlefield(battlefield);
+
+    assert!(editor
+        .battlefield()
+        .get_all()
+        .iter()
+        .flatten()
+        .all(|cell| cell.state == CellState::Hidden));
+}
+
+#[test]
+fn should_build_the_board_again_from_ids_of_mines() {
+    use crate::engine::Mask;
+
+    let mask = Mask::from_ascii("###.\n####\n####").unwrap();
+    let config = GameConfig::new(0, 0, 0).with_mask(mask);
+    let mut editor = BoardEditor::new(&config);
+    editor
+        .randomize_region(
+            CellPosition { x: 0, y: 0 },
+            CellPosition { x: 3, y: 2 },
+            4,
+            Some(5),
+        )
+        .unwrap();
+
+    let mut rebuilt = BoardEditor::new(&config);
+    rebuilt.toggle_mine(CellPosition { x: 0, y: 0 }).unwrap();
+    rebuilt.set_mines(&editor.mines()).unwrap();
+
+    assert_eq!(rebuilt.mines(), editor.mines());
+    assert!(rebuilt
+        .battlefield()
+        .get_all()
+        .iter()
+        .flatten()
+        .zip(editor.battlefield().get_all().iter().flatten())
+        .all(|(rebuilt, cell)| rebuilt.ctype == cell.ctype));
+    assert_eq!(rebuilt.battlefield().bombs(), 4);
+    assert_values(rebuilt.battlefield());
+
+    let void = editor
+        .battlefield()
+        .get_by_position(CellPosition { x: 3, y: 0 })
+        .unwrap()
+        .id;
+    assert!(rebuilt.set_mines(&[0, void]).is_err());
+    assert!(rebuilt.set_mines(&[0, 100]).is_err());
+    assert_eq!(rebuilt.mines(), editor.mines());
+}
+
+#[test]
+fn should_play_with_rules_of_the_config() {
+    let config = GameConfig::new(3, 3, 0)
+        .with_win_rule(WinRule::RevealOrFlagAll)
+        .with_guess_mode(GuessMode::Kind);
+    let mut editor = BoardEditor::new(&config);
+    editor.toggle_mine(CellPosition { x: 1, y: 1 }).unwrap();
+
+    let game = editor.play();
+
+    assert_eq!(game.win_rule(), WinRule::RevealOrFlagAll);
+    assert_eq!(game.guess_mode(), GuessMode::Kind);
+    assert_eq!(game.battlefield().bombs(), 1);
+}
@@ -0,0 +1,311 @@
+use crate::engine::cell::*;
+use crate::engine::game::Game;
+use crate::engine::topology::Topology;
+
+/// The cell packed into 16 bits
+///
+///  - bits `0..8` - signed value of the empty cell or count of mines of the mine
+///  - bits `8..11` - state of the cell (`STATE_*` constants)
+///  - bits `11..13` - type of the cell (`TYPE_*` constants)
+///  - bits `13..16` - count of flags of the flagged cell
+///
+/// Counts of flags above `FLAGS_MAX` are stored as `FLAGS_MAX`,
+///  so clients show them as "7 or more"
+pub type EncodedCell = u16;
+
+pub const VALUE_MASK: EncodedCell = 0x00ff;
+
+pub const STATE_SHIFT: u32 = 8;
+pub const STATE_MASK: EncodedCell = 0b111 << STATE_SHIFT;
+pub const STATE_HIDDEN: EncodedCell = 0;
+pub const STATE_REVEALED: EncodedCell = 1;
+pub const STATE_FLAGGED: EncodedCell = 2;
+pub const STATE_VOID: EncodedCell = 3;
+pub const STATE_NEGATIVE_FLAGGED: EncodedCell = 4;
+pub const STATE_EXPLODED: EncodedCell = 5;
+pub const STATE_WRONG_FLAG: EncodedCell = 6;
+
+pub const TYPE_SHIFT: u32 = 11;
+pub const TYPE_MASK: EncodedCell = 0b11 << TYPE_SHIFT;
+pub const TYPE_UNKNOWN: EncodedCell = 0;
+pub const TYPE_EMPTY: EncodedCell = 1;
+pub const TYPE_MINE: EncodedCell = 2;
+pub const TYPE_NEGATIVE_MINE: EncodedCell = 3;
+
+pub const FLAGS_SHIFT: u32 = 13;
+pub const FLAGS_MASK: EncodedCell = 0b111 << FLAGS_SHIFT;
+pub const FLAGS_MAX: EncodedCell = FLAGS_MASK >> FLAGS_SHIFT;
+
+/// Packs the cell as the player sees it into 16 bits
+pub fn encode(cell: &PlayerCell) -> EncodedCell {
+    let state = match cell.state {
+        CellState::Hidden => STATE_HIDDEN,
+        CellState::Revealed => STATE_REVEALED,
+        CellState::Flagged(_) => STATE_FLAGGED,
+        CellState::NegativeFlagged(_) => STATE_NEGATIVE_FLAGGED,
+        CellState::Exploded => STATE_EXPLODED,
+        CellState::WrongFlag => STATE_WRONG_FLAG,
+        CellState::Void => STATE_VOID,
+    };
+    let flags = (cell.state.flags() as EncodedCell).min(FLAGS_MAX);
+
+    let (ctype, value) = match cell.ctype {
+        None => (TYPE_UNKNOWN, 0),
+        Some(CellType::Empty(value)) => (TYPE_EMPTY, value as u8 as EncodedCell),
+        Some(CellType::Mine(mines)) => (TYPE_MINE, mines as EncodedCell),
+        Some(CellType::NegativeMine(mines)) => (TYPE_NEGATIVE_MINE, mines as EncodedCell),
+    };
+
+    value | state << STATE_SHIFT | ctype << TYPE_SHIFT | flags << FLAGS_SHIFT
+}
+
+/// Unpacks the state and the type of the cell
+///  which were packed by `encode`
+pub fn decode(encoded: EncodedCell) -> (CellState, Option<CellType>) {
+    let flags = ((encoded & FLAGS_MASK) >> FLAGS_SHIFT) as u8;
+    let state = match (encoded & STATE_MASK) >> STATE_SHIFT {
+        STATE_REVEALED => CellState::Revealed,
+        STATE_FLAGGED => CellState::Flagged(flags),
+        STATE_NEGATIVE_FLAGGED => CellState::NegativeFlagged(flags),
+        STATE_EXPLODED => CellState::Exploded,
+        STATE_WRONG_FLAG => CellState::WrongFlag,
+        STATE_VOID => CellState::Void,
+        _ => CellState::Hidden,
+    };
+
+    let ctype = match (encoded & TYPE_MASK) >> TYPE_SHIFT {
+        TYPE_EMPTY => Some(CellType::Empty((encoded & VALUE_MASK) as u8 as i8)),
+        TYPE_MINE => Some(CellType::Mine((encoded & VALUE_MASK) as u8)),
+        TYPE_NEGATIVE_MINE => Some(CellType::NegativeMine((encoded & VALUE_MASK) as u8)),
+        _ => None,
+    };
+
+    (state, ctype)
+}
+
+/// The whole map packed into a flat buffer indexed by cell id
+///  and the list of cells which were changed by the last action
+///
+/// Both buffers are kept between actions so the client
+///  may read them directly from the memory without copying
+pub struct EncodedBoard {
+    cells: Vec<EncodedCell>,
+    diff: Vec<CellId>,
+}
+
+impl EncodedBoard {
+    /// Encodes every cell of the game as the player sees it
+    pub fn new<T: Topology>(game: &Game<T>) -> Self {
+        let battlefield = game.battlefield();
+        let mut board = Self {
+            cells: vec![0; battlefield.cells_count()],
+            diff: vec![],
+        };
+
+        board.encode_all(game);
+        board.diff.clear();
+
+        board
+    }
+
+    /// Re-encodes cells which were changed by the last action
+    ///  and replaces the diff with the cells which look different
+    ///
+    /// When the game has ended all cells are re-encoded because
+    ///  the player is allowed to see types of all of them
+    pub fn update<T: Topology>(&mut self, game: &Game<T>, changed: &[CellId]) {
+        self.diff.clear();
+
+        if !game.is_played() {
+            self.encode_all(game);
+
+            return;
+        }
+
+        for cell_id in changed {
+            let cell = game.battlefield().get(*cell_id);
+            self.encode_cell(&game.player_cell(cell));
+        }
+    }
+
+    /// Re-encodes the whole board after the game has been started
+    ///  over and puts every cell which looks different into the diff
+    ///
+    /// The buffer is resized when the new battlefield has another size
+    pub fn reset<T: Topology>(&mut self, game: &Game<T>) {
+        let battlefield = game.battlefield();
+
+        self.diff.clear();
+        self.cells.truncate(battlefield.cells_count());
+        self.encode_all(game);
+    }
+
+    /// Returns all packed cells where index is the cell id
+    pub fn cells(&self) -> &[EncodedCell] {
+        &self.cells
+    }
+
+    /// Returns ids of cells which were changed by the last action
+    pub fn diff(&self) -> &[CellId] {
+        &self.diff
+    }
+
+    fn encode_all<T: Topology>(&mut self, game: &Game<T>) {
+        for cell in game.battlefield().get_all().iter().flatten() {
+            self.encode_cell(&game.player_cell(cell));
+        }
+    }
+
+    /// Writes the cell into the buffer and marks it as changed
+    ///  if it differs from the previous value
+    ///
+    /// Every cell is written at most once per update
+    ///  so the diff never contains duplicates
+    fn encode_cell(&mut self, cell: &PlayerCell) {
+        let index = cell.id as usize;
+        let encoded = encode(cell);
+
+        if index >= self.cells.len() {
+            self.cells.resize(index + 1, 0);
+        }
+
+        if self.cells[index] != encoded {
+            self.cells[index] = encoded;
+            self.diff.push(cell.id);
+        }
+    }
+}
+
+#[test]
+fn should_decode_encoded_cell() {
+    let cells = [
+        Cell::new(0, CellType::Empty(3), CellPosition { x: 0, y: 0 }),
+        Cell::new(1, CellType::Mine(1), CellPosition { x: 0, y: 1 }),
+        Cell::new(2, CellType::Empty(-2), CellPosition { x: 0, y: 2 }),
+        Cell::new(3, CellType::NegativeMine(2), CellPosition { x: 0, y: 3 }),
+        Cell {
+            state: CellState::NegativeFlagged(3),
+            ..Cell::new(4, CellType::NegativeMine(3), CellPosition { x: 0, y: 4 })
+        },
+        Cell {
+            state: CellState::Exploded,
+            ..Cell::new(5, CellType::Mine(2), CellPosition { x: 0, y: 5 })
+        },
+        Cell {
+            state: CellState::WrongFlag,
+            ..Cell::new(6, CellType::Empty(1), CellPosition { x: 0, y: 6 })
+        },
+    ];
+
+    for cell in cells.iter() {
+        for is_game_over in [false, true].iter() {
+            let player_cell = cell.player_view(*is_game_over);
+
+            assert_eq!(
+                decode(encode(&player_cell)),
+                (player_cell.state, player_cell.ctype)
+            );
+        }
+    }
+}
+
+#[test]
+fn should_saturate_counts_of_flags_which_do_not_fit() {
+    use crate::engine::MAX_MINES_PER_CELL;
+
+    let max = MAX_MINES_PER_CELL;
+    let cell = |state, ctype| PlayerCell {
+        id: 0,
+        ctype,
+        state,
+        position: CellPosition { x: 0, y: 0 },
+    };
+
+    let cases = [
+        (cell(CellState::Flagged(7), None), CellState::Flagged(7)),
+        (cell(CellState::Flagged(8), None), CellState::Flagged(7)),
+        (cell(CellState::Flagged(max), None), CellState::Flagged(7)),
+        (
+            cell(
+                CellState::NegativeFlagged(max),
+                Some(CellType::NegativeMine(max)),
+            ),
+            CellState::NegativeFlagged(7),
+        ),
+    ];
+
+    for (cell, state) in cases.iter() {
+        assert_eq!(decode(encode(cell)), (*state, cell.ctype));
+    }
+
+    let types = [
+        CellType::Mine(max),
+        CellType::Empty(i8::MIN),
+        CellType::Empty(i8::MAX),
+    ];
+
+    for ctype in types.iter() {
+        let cell = cell(CellState::Exploded, Some(*ctype));
+
+        assert_eq!(decode(encode(&cell)), (cell.state, cell.ctype));
+    }
+}
+
+#[test]
+fn should_encode_revealed_cell_with_its_value() {
+    let mut cell = Cell::new(0, CellType::Empty(5), CellPosition { x: 0, y: 0 });
+    cell.reveal();
+
+    let encoded = encode(&cell.player_view(false));
+
+    assert_eq!(encoded & VALUE_MASK, 5);
+    assert_eq!((encoded & STATE_MASK) >> STATE_SHIFT, STATE_REVEALED);
+    assert_eq!((encoded & TYPE_MASK) >> TYPE_SHIFT, TYPE_EMPTY);
+}
+
+#[test]
+fn should_not_leak_hidden_mines() {
+    let cell = Cell::new(0, CellType::Mine(1), CellPosition { x: 0, y: 0 });
+
+    assert_eq!(encode(&cell.player_view(false)), 0);
+}
+
+#[test]
+fn should_contain_only_changed_cells_in_diff() {
+    let mut game = Game::with_seed(5, 5, 3, 7);
+    let mut board = EncodedBoard::new(&game);
+
+    assert_eq!(board.cells().len(), 25);
+    assert!(board.diff().is_empty());
+
+    let flagged = game.flag(0);
+    board.update(&game, &[flagged.id]);
+
+    assert_eq!(board.diff(), &[0]);
+    assert_eq!(decode(board.cells()[0]), (CellState::Flagged(1), None));
+
+    // Nothing has been changed
+    board.update(&game, &[flagged.id]);
+
+    assert!(board.diff().is_empty());
+}
+
+#[test]
+fn should_put_changed_cells_into_diff_on_reset() {
+    let mut game = Game::with_seed(5, 5, 3, 7);
+    let mut board = EncodedBoard::new(&game);
+
+    let flagged = game.flag(0);
+    board.update(&game, &[flagged.id]);
+
+    game.restart();
+    board.reset(&game);
+
+    assert_eq!(board.diff(), &[0]);
+    assert_eq!(board.cells(), EncodedBoard::new(&game).cells());
+
+    game.new_game(&crate::engine::GameConfig::new(3, 4, 2));
+    board.reset(&game);
+
+    assert_eq!(board.cells().len(), 12);
+}
@@ -0,0 +1,82 @@
+use crate::engine::cell::Cell;
+
+/// Everything that may happen with the game
+///  and the client may want to know about
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    /// The first cell has been revealed and the game clock
+    ///  should start ticking
+    TimerStarted,
+
+    /// The cell has been uncovered
+    CellRevealed(Cell),
+
+    /// The cell has been marked as a potential bomb
+    CellFlagged(Cell),
+
+    /// The flag has been removed from the cell
+    CellUnflagged(Cell),
+
+    /// The player has revealed the bomb, it takes a life
+    ///  and the game is lost if it was the last one
+    MineDetonated(Cell),
+
+    /// All empty cells have been revealed
+    GameWon,
+
+    /// The bomb has been revealed and the game is over
+    GameLost,
+
+    /// The game has been started over, so the whole
+    ///  battlefield has to be rendered again
+    GameReset,
+}
+
+/// Identificator of the listener which is used to unsubscribe
+pub type SubscriptionId = u32;
+
+type Listener = Box<dyn FnMut(&GameEvent)>;
+
+/// Keeps all listeners of game events
+///  and delivers every event to each of them
+#[derive(Default)]
+pub struct EventEmitter {
+    /// Identificator for the next listener
+    next_id: SubscriptionId,
+
+    listeners: Vec<(SubscriptionId, Listener)>,
+}
+
+impl EventEmitter {
+    /// Adds the listener and returns the identificator
+    ///  which may be used to remove it
+    pub fn subscribe<F>(&mut self, listener: F) -> SubscriptionId
+    where
+        F: FnMut(&GameEvent) + 'static,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.listeners.push((id, Box::new(listener)));
+
+        id
+    }
+
+    /// Removes the listener by provided `id`
+    ///
+    /// Returns `false` if there was no such listener
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let listeners_count = self.listeners.len();
+        self.listeners.retain(|(listener_id, _)| *listener_id != id);
+
+        self.listeners.len() != listeners_count
+    }
+
+    /// Delivers the event to all listeners
+    ///  in the order they have been subscribed
+    pub fn emit(&mut self, event: GameEvent) {
+        for (_, listener) in self.listeners.iter_mut() {
+            listener(&event);
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use crate::engine::battlefield::{BattleField, LossReport, Reveal};
+use crate::engine::cell::*;
+use crate::engine::config::{GameConfig, GuessMode, WinRule};
+use crate::engine::events::{EventEmitter, GameEvent, SubscriptionId};
+use crate::engine::grid::Grid;
+use crate::engine::solver::Solver;
+use crate::engine::topology::Topology;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GameStatus {
+    /// Signals to the player that the game is going
+    Played,
+
+    /// Signals to the player that the game has ended and the user is lost
+    Lose,
+
+    /// Signals to the player that the game has ended and the user is won
+    Won,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GameState {
+    pub status: GameStatus,
+    pub flags: u16,
+
+    /// How many mines the player may still hit
+    pub lives: u8,
+}
+
+/// The game itself which contain the battlefield
+///  and decides when the game is won or lost
+pub struct Game<T: Topology = Grid> {
+    /// The main battlefield of mine sweeper
+    battlefield: BattleField<T>,
+
+    /// Returns the game state of the game
+    game_state: GameState,
+
+    /// How many cells should we reveal to win the game
+    elements_to_win_the_game: usize,
+
+    /// How many cells already revealed
+    revealed_elements: usize,
+
+    /// Becomes `true` when the first cell has been revealed
+    is_started: bool,
+
+    /// What happened to mines and flags if the game has been lost
+    loss_report: Option<LossReport>,
+
+    /// When the game is won
+    win_rule: WinRule,
+
+    /// What happens to mines when the player guesses
+    guess_mode: GuessMode,
+
+    /// Listeners of game events
+    events: EventEmitter,
+}
+
+/// Public interface for games on built-in battlefields
+impl Game {
+    /// Creates the game with random battlefield by providing
+    ///  rows, columns and bombs
+    pub fn new(rows: usize, cols: usize, bombs: u16) -> Self {
+        Self::from_battlefield(BattleField::new(rows, cols, bombs))
+    }
+
+    /// Creates the game with the battlefield which is generated
+    ///  from provided `seed`
+    pub fn with_seed(rows: usize, cols: usize, bombs: u16, seed: u64) -> Self {
+        Self::from_battlefield(BattleField::with_seed(rows, cols, bombs, seed))
+    }
+
+    /// Creates the game by provided config
+    ///
+    /// Panics if the config is invalid, see `GameConfig::validate`
+    pub fn from_config(config: &GameConfig) -> Self {
+        let mut game = Self::from_battlefield(BattleField::from_config(config));
+        game.win_rule = config.win_rule;
+        game.guess_mode = config.guess_mode;
+
+        game
+    }
+
+    /// Starts a new game with a new battlefield created by
+    ///  provided config
+    ///
+    /// Listeners are kept and notified by `GameEvent::GameReset`
+    ///
+    /// Panics if the config is invalid, see `GameConfig::validate`
+    pub fn new_game(&mut self, config: &GameConfig) {
+        self.battlefield = BattleField::from_config(config);
+        self.win_rule = config.win_rule;
+        self.guess_mode = config.guess_mode;
+        self.reset();
+    }
+
+    /// Starts a new game on the same kind of battlefield
+    ///  which is generated from provided `seed`
+    ///
+    /// Listeners are kept and notified by `GameEvent::GameReset`
+    pub fn rematch(&mut self, seed: u64) {
+        let config = self
+            .battlefield
+            .config()
+            .with_seed(seed)
+            .with_win_rule(self.win_rule)
+            .with_guess_mode(self.guess_mode);
+
+        self.new_game(&config);
+    }
+}
+
+/// Public interface for games on battlefields of any geometry
+impl<T: Topology> Game<T> {
+    /// Creates the game on top of already existing battlefield
+    pub fn from_battlefield(battlefield: BattleField<T>) -> Self {
+        let mut game = Self {
+            battlefield,
+            game_state: GameState {
+                status: GameStatus::Played,
+                flags: 0,
+                lives: 0,
+            },
+            elements_to_win_the_game: 0,
+            revealed_elements: 0,
+            is_started: false,
+            loss_report: None,
+            win_rule: WinRule::RevealAll,
+            guess_mode: GuessMode::Normal,
+            events: EventEmitter::default(),
+        };
+        game.reset_state();
+
+        game
+    }
+
+    /// Starts the same map over with all cells hidden
+    ///
+    /// Listeners are kept and notified by `GameEvent::GameReset`
+    pub fn restart(&mut self) {
+        self.battlefield.restart();
+        self.reset();
+    }
+
+    /// Reveals the cell by providing id
+    ///
+    /// Does nothing if the game has already ended
+    ///  or the cell has already been revealed
+    ///
+    /// With `GuessMode::Kind` the mine is moved away from the cell
+    ///  if the solver can't prove any cell safe, with `GuessMode::Cruel`
+    ///  the mine is moved into the cell if it isn't proved safe
+    pub fn reveal(&mut self, cell_id: CellId) -> Reveal {
+        let state = self.battlefield.get(cell_id).state;
+
+        if !self.is_played()
+            || state == CellState::Revealed
+            || state == CellState::Void
+            || state == CellState::Exploded
+        {
+            return Reveal::default();
+        }
+
+        let is_mine = self.battlefield.get(cell_id).ctype.is_mine();
+
+        match self.guess_mode {
+            GuessMode::Kind if is_mine && !Solver::new(&self.battlefield).has_safe_cell() => {
+                self.battlefield.relocate_mines(cell_id, false);
+            }
+            // The layout is found only if the cell isn't proved safe
+            //  by all clues, so the solver isn't asked
+            GuessMode::Cruel if !is_mine && self.is_started => {
+                self.battlefield.relocate_mines(cell_id, true);
+            }
+            _ => {}
+        }
+
+        let mut reveal = self.battlefield.reveal(cell_id);
+        self.apply_reveal(&mut reveal);
+
+        reveal
+    }
+
+    /// Reveals all hidden neighbours of the revealed cell
+    ///  if the cell has as many flags around as its value
+    pub fn chord(&mut self, cell_id: CellId) -> Reveal {
+        if !self.is_played() {
+            return Reveal::default();
+        }
+
+        let mut reveal = self.battlefield.chord(cell_id);
+        self.apply_reveal(&mut reveal);
+
+        reveal
+    }
+
+    /// Flags or unflags the cell by providing id
+    ///  and returns the actual cell
+    ///
+    /// With `WinRule::RevealOrFlagAll` the game is won
+    ///  as soon as all mines are flagged correctly
+    pub fn flag(&mut self, cell_id: CellId) -> Cell {
+        if !self.is_played() {
+            return *self.battlefield.get(cell_id);
+        }
+
+        let previous_state = self.battlefield.get(cell_id).state;
+        let cell = *self.battlefield.flag(cell_id);
+        self.game_state.flags = self.battlefield.flags_left;
+
+        // Every change of flags is reported, so the client knows
+        //  how many flags of which kind the cell has
+        if cell.state.is_flagged() && cell.state != previous_state {
+            self.events.emit(GameEvent::CellFlagged(cell));
+        } else if previous_state.is_flagged() && cell.state == CellState::Hidden {
+            self.events.emit(GameEvent::CellUnflagged(cell));
+        }
+
+        if self.is_won_by_flags() {
+            self.win();
+        }
+
+        cell
+    }
+
+    /// Adds the listener of game events and returns
+    ///  the identificator to unsubscribe it later
+    pub fn subscribe<F>(&mut self, listener: F) -> SubscriptionId
+    where
+        F: FnMut(&GameEvent) + 'static,
+    {
+        self.events.subscribe(listener)
+    }
+
+    /// Removes the listener of game events
+    ///
+    /// Returns `false` if there was no such listener
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.events.unsubscribe(id)
+    }
+
+    /// Returns a game state of the game
+    pub fn state(&self) -> GameState {
+        self.game_state
+    }
+
+    /// Returns the battlefield of the game
+    pub fn battlefield(&self) -> &BattleField<T> {
+        &self.battlefield
+    }
+
+    /// Returns when the game is won
+    pub fn win_rule(&self) -> WinRule {
+        self.win_rule
+    }
+
+    /// Changes when the game is won, the rule is checked
+    ///  on the next reveal or flag
+    pub fn set_win_rule(&mut self, win_rule: WinRule) {
+        self.win_rule = win_rule;
+    }
+
+    /// Returns what happens to mines when the player guesses
+    pub fn guess_mode(&self) -> GuessMode {
+        self.guess_mode
+    }
+
+    /// Changes what happens to mines when the player guesses
+    ///  or returns the error if the mode can't move mines
+    ///  of the battlefield, see `GuessMode`
+    pub fn set_guess_mode(&mut self, guess_mode: GuessMode) -> Result<(), String> {
+        guess_mode.check(
+            self.battlefield.max_mines_per_cell(),
+            self.battlefield.negative_bombs(),
+        )?;
+        self.guess_mode = guess_mode;
+
+        Ok(())
+    }
+
+    /// Returns the summary of mines and flags
+    ///  if the game has been lost
+    pub fn loss_report(&self) -> Option<&LossReport> {
+        self.loss_report.as_ref()
+    }
+
+    /// Returns the cell as the player sees it: types of hidden
+    ///  cells are exposed only when the game has ended
+    pub fn player_cell(&self, cell: &Cell) -> PlayerCell {
+        cell.player_view(!self.is_played())
+    }
+
+    /// Returns the whole map as the player sees it
+    pub fn player_view(&self) -> Vec<Vec<PlayerCell>> {
+        self.battlefield
+            .get_all()
+            .iter()
+            .map(|col| col.iter().map(|cell| self.player_cell(cell)).collect())
+            .collect()
+    }
+
+    /// Returns `true` if the game is still going
+    pub fn is_played(&self) -> bool {
+        self.game_state.status == GameStatus::Played
+    }
+
+    fn reset(&mut self) {
+        self.reset_state();
+        self.events.emit(GameEvent::GameReset);
+    }
+
+    /// Brings counters and the game state in line
+    ///  with the untouched battlefield
+    fn reset_state(&mut self) {
+        self.elements_to_win_the_game = self.battlefield.cells_count()
+            - self.battlefield.void_cells_count()
+            - self.battlefield.mined_cells_count();
+        self.revealed_elements = 0;
+        self.is_started = false;
+        self.loss_report = None;
+        self.game_state = GameState {
+            status: GameStatus::Played,
+            flags: self.battlefield.flags_left,
+            lives: self.battlefield.lives_left,
+        };
+    }
+
+    /// Updates the game state based on the revealed cells
+    ///  and notifies listeners about all changes
+    ///
+    /// When the game is won all mines are flagged
+    ///  and put into `flagged` of the `reveal`
+    fn apply_reveal(&mut self, reveal: &mut Reveal) {
+        if reveal.cells.is_empty() {
+            return;
+        }
+
+        if !self.is_started {
+            self.is_started = true;
+            self.events.emit(GameEvent::TimerStarted);
+        }
+
+        // Add into `revealed_elements` all new revealed cells count,
+        //  exploded mines don't bring the player closer to the win
+        self.revealed_elements += reveal
+            .cells
+            .iter()
+            .filter(|cell| !cell.ctype.is_mine())
+            .count();
+        self.game_state.flags = self.battlefield.flags_left;
+        self.game_state.lives = self.battlefield.lives_left;
+
+        for cell in reveal.cells.iter() {
+            self.events.emit(GameEvent::CellRevealed(*cell));
+
+            // The mine which ends the game is reported after
+            //  the whole map has been revealed
+            if cell.state == CellState::Exploded && !reveal.game_is_over {
+                self.events.emit(GameEvent::MineDetonated(*cell));
+            }
+        }
+
+        // Updates `game_is_over` flag to set the actual game state
+        if let Some(loss) = &reveal.loss {
+            self.game_state.status = GameStatus::Lose;
+            self.loss_report = Some(loss.clone());
+
+            self.events.emit(GameEvent::MineDetonated(loss.detonated));
+            self.events.emit(GameEvent::GameLost);
+        } else if self.revealed_elements >= self.elements_to_win_the_game || self.is_won_by_flags()
+        {
+            reveal.flagged = self.win();
+        }
+    }
+
+    /// Returns `true` if the win rule allows to win by flags
+    ///  and every mine is flagged correctly
+    ///
+    /// Boards without mines can't be won by flags,
+    ///  otherwise any flag would win them
+    fn is_won_by_flags(&self) -> bool {
+        self.win_rule == WinRule::RevealOrFlagAll
+            && self.battlefield.mined_cells_count() > 0
+            && self.battlefield.are_all_mines_flagged()
+    }
+
+    /// Ends the game with the win, flags all mines
+    ///  and returns cells which have been flagged
+    fn win(&mut self) -> Vec<Cell> {
+        self.game_state.status = GameStatus::Won;
+
+        let flagged = self.battlefield.flag_all_mines();
+        self.game_state.flags = self.battlefield.flags_left;
+
+        for cell in flagged.iter() {
+            self.events.emit(GameEvent::CellFlagged(*cell));
+        }
+
+        self.events.emit(GameEvent::GameWon);
+
+        flagged
+    }
+}
@@ -0,0 +1,212 @@
+use std::convert::TryFrom;
+
+use crate::engine::cell::*;
+use crate::engine::config::GameConfig;
+use crate::engine::mask::Mask;
+use crate::engine::topology::{Neighbourhood, Tiling, Topology};
+
+/// Geometry of all built-in battlefields: layers of `rows x cols`
+///  square or hexagonal cells which may wrap around the edges
+///  and may have an irregular shape
+///
+/// Layers are stacked along the `y` axis of the map,
+///  see `CellCoordinates` for details
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid {
+    rows: usize,
+    cols: usize,
+
+    /// How many layers of `rows x cols` the map has
+    layers: usize,
+
+    /// The shape of cells
+    tiling: Tiling,
+
+    /// Cells which are counted by values of empty cells
+    neighbourhood: Neighbourhood,
+
+    /// Offsets of neighbours which are calculated
+    ///  from the tiling and the neighbourhood
+    offsets: Vec<(i16, i16)>,
+
+    /// Whether opposite edges of the map are neighbours
+    wrap: bool,
+
+    /// The shape of every layer if the map isn't a rectangle
+    mask: Option<Mask>,
+}
+
+impl Grid {
+    /// Creates the geometry described by provided config
+    pub fn new(config: &GameConfig) -> Self {
+        Self {
+            rows: config.rows,
+            cols: config.cols,
+            layers: config.layers.max(1),
+            tiling: config.tiling,
+            neighbourhood: config.neighbourhood.clone(),
+            offsets: config.neighbourhood.offsets(config.tiling),
+            wrap: config.wrap,
+            mask: config.mask.clone(),
+        }
+    }
+
+    /// Returns how many rows every layer has
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns how many columns every layer has
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns how many layers the map has
+    pub fn layers(&self) -> usize {
+        self.layers
+    }
+
+    /// Returns the shape of cells
+    pub fn tiling(&self) -> Tiling {
+        self.tiling
+    }
+
+    /// Returns cells which are counted by values of empty cells
+    pub fn neighbourhood(&self) -> &Neighbourhood {
+        &self.neighbourhood
+    }
+
+    /// Returns `true` if opposite edges of the map are neighbours
+    pub fn is_wrapped(&self) -> bool {
+        self.wrap
+    }
+
+    /// Returns the shape of every layer if the map isn't a rectangle
+    pub fn mask(&self) -> Option<&Mask> {
+        self.mask.as_ref()
+    }
+
+    /// Returns the config which describes the same geometry
+    ///  with provided count of `bombs`
+    pub fn config(&self, bombs: u16) -> GameConfig {
+        let config = GameConfig::new(self.rows, self.cols, bombs)
+            .with_layers(self.layers)
+            .with_tiling(self.tiling)
+            .with_neighbourhood(self.neighbourhood.clone())
+            .with_wrap(self.wrap);
+
+        match &self.mask {
+            Some(mask) => config.with_mask(mask.clone()),
+            None => config,
+        }
+    }
+
+    /// Converts the position on the map into coordinates
+    ///  with the layer of the cell
+    pub fn coordinates(&self, position: CellPosition) -> CellCoordinates {
+        let rows = self.rows.max(1) as i16;
+
+        CellCoordinates {
+            x: position.x,
+            y: position.y.rem_euclid(rows),
+            z: position.y.div_euclid(rows),
+        }
+    }
+
+    /// Converts coordinates with the layer into the position on the map
+    pub fn position(&self, coordinates: CellCoordinates) -> CellPosition {
+        CellPosition {
+            x: coordinates.x,
+            y: coordinates.z * self.rows as i16 + coordinates.y,
+        }
+    }
+
+    /// Returns `true` if coordinates are inside of the map
+    pub fn is_inside(&self, coordinates: CellCoordinates) -> bool {
+        coordinates.x >= 0
+            && coordinates.x < self.cols as i16
+            && coordinates.y >= 0
+            && coordinates.y < self.rows as i16
+            && coordinates.z >= 0
+            && coordinates.z < self.layers as i16
+    }
+
+    /// Moves coordinates which are outside of the map
+    ///  to the opposite edge
+    fn wrap_coordinates(&self, (x, y, z): (i32, i32, i32)) -> (i32, i32, i32) {
+        if self.cols == 0 || self.rows == 0 {
+            return (x, y, z);
+        }
+
+        (
+            x.rem_euclid(self.cols as i32),
+            y.rem_euclid(self.rows as i32),
+            z.rem_euclid(self.layers as i32),
+        )
+    }
+}
+
+impl Topology for Grid {
+    fn width(&self) -> usize {
+        self.cols
+    }
+
+    fn height(&self) -> usize {
+        self.rows * self.layers
+    }
+
+    /// Every layer has the same shape of the mask
+    fn contains(&self, position: CellPosition) -> bool {
+        let coordinates = self.coordinates(position);
+
+        match &self.mask {
+            Some(mask) => mask.contains(coordinates.x as usize, coordinates.y as usize),
+            None => true,
+        }
+    }
+
+    /// Cells of maps with several layers also have neighbours on
+    ///  the layers above and below: the cells right above and below
+    ///  and cells around them in the same shape as in the layer
+    fn neighbours(&self, position: CellPosition) -> Vec<CellPosition> {
+        let coordinates = self.coordinates(position);
+        let layer_offsets: &[i16] = if self.layers > 1 { &[0, -1, 1] } else { &[0] };
+
+        let mut neighbours = Vec::with_capacity(self.offsets.len() * layer_offsets.len());
+
+        for z in layer_offsets {
+            let offsets = self
+                .offsets
+                .iter()
+                .chain(if *z == 0 { None } else { Some(&(0, 0)) });
+
+            for (x, y) in offsets {
+                // Coordinates are summed in `i32`, so far offsets
+                //  of cells on the edge of the largest map don't overflow
+                let neighbour = (
+                    i32::from(coordinates.x) + i32::from(*x),
+                    i32::from(coordinates.y) + i32::from(*y),
+                    i32::from(coordinates.z) + i32::from(*z),
+                );
+                let (x, y, z) = if self.wrap {
+                    self.wrap_coordinates(neighbour)
+                } else {
+                    neighbour
+                };
+
+                // Coordinates which don't fit `i16` are outside of the map
+                if let (Ok(x), Ok(y), Ok(z)) =
+                    (i16::try_from(x), i16::try_from(y), i16::try_from(z))
+                {
+                    let neighbour_coordinates = CellCoordinates { x, y, z };
+
+                    if self.is_inside(neighbour_coordinates) {
+                        neighbours.push(self.position(neighbour_coordinates));
+                    }
+                }
+            }
+        }
+
+        neighbours
+    }
+}
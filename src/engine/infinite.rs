@@ -0,0 +1,507 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::engine::cell::*;
+use crate::engine::topology::{Neighbourhood, Tiling};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+/// How many cells every side of the chunk has
+pub const CHUNK_SIZE: usize = 16;
+
+/// How many cells every chunk has
+const CHUNK_CELLS: usize = CHUNK_SIZE * CHUNK_SIZE;
+
+/// How many cells a single reveal may open, so the flood fill
+///  stops even if the opening spreads endlessly
+///
+/// Empty cells on the border of such reveal stay revealed
+///  with hidden neighbours, which are safe to reveal later
+pub const MAX_FLOOD_FILL: usize = 16 * CHUNK_CELLS;
+
+/// How many chunks are kept by default
+const DEFAULT_MAX_CHUNKS: usize = 1024;
+
+/// At least the chunk and all chunks around it should be kept,
+///  otherwise counting mines around a cell evicts chunks in a loop
+const MIN_MAX_CHUNKS: usize = 9;
+
+/// Position of the cell on the infinite board
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct InfinitePosition {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// Position of the chunk where the chunk `(0, 0)` contains cells
+///  from `(0, 0)` to `(CHUNK_SIZE - 1, CHUNK_SIZE - 1)`
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ChunkPosition {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl InfinitePosition {
+    /// Returns the chunk which contains the cell
+    ///  and the index of the cell inside of it
+    fn locate(self) -> (ChunkPosition, usize) {
+        let size = CHUNK_SIZE as i64;
+        let chunk = ChunkPosition {
+            x: self.x.div_euclid(size),
+            y: self.y.div_euclid(size),
+        };
+
+        // The same order as ids of the battlefield: `x * height + y`
+        let index = self.x.rem_euclid(size) * size + self.y.rem_euclid(size);
+
+        (chunk, index as usize)
+    }
+}
+
+/// The cell of the infinite board
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InfiniteCell {
+    pub position: InfinitePosition,
+    pub ctype: CellType,
+    pub state: CellState,
+}
+
+/// Mines of the square of `CHUNK_SIZE x CHUNK_SIZE` cells
+struct Chunk {
+    /// Whether the cell contains a mine, indexed the same way
+    ///  as `InfinitePosition::locate` does
+    mines: Vec<bool>,
+
+    /// When the chunk has been used last time
+    ///  to find which chunk should be evicted
+    used_at: u64,
+}
+
+/// The board without edges which is generated chunk by chunk
+///  when the player or the flood fill reaches them
+///
+/// Every chunk has the same count of mines placed based on the seed
+///  and the position of the chunk, so chunks may be dropped
+///  and generated again later. The store keeps at most `max_chunks`
+///  of them and drops the least recently used one when it's full
+///
+/// States are kept apart from chunks and only for cells which aren't
+///  hidden, so the memory grows with cells which the player has
+///  revealed or flagged, but not with chunks which have been visited
+pub struct InfiniteBoard {
+    seed: u64,
+
+    /// How many mines every chunk contains
+    mines_per_chunk: u16,
+
+    /// How many chunks are kept before the least recently used one is evicted
+    max_chunks: usize,
+
+    chunks: HashMap<ChunkPosition, Chunk>,
+
+    /// States of all cells which aren't hidden
+    states: HashMap<InfinitePosition, CellState>,
+
+    /// Counter of chunk accesses which orders chunks by their use
+    clock: u64,
+
+    /// How many cells have been revealed, it's the score of the game
+    cleared: u64,
+
+    /// Becomes `true` when the player has hit a mine
+    is_lost: bool,
+
+    /// Offsets of neighbours which are counted by values of empty cells
+    offsets: Vec<(i16, i16)>,
+}
+
+impl InfiniteBoard {
+    /// Creates the board where every chunk contains `mines_per_chunk`
+    ///  mines placed based on the `seed`
+    pub fn new(seed: u64, mines_per_chunk: u16) -> Self {
+        Self {
+            seed,
+            mines_per_chunk: mines_per_chunk.min(CHUNK_CELLS as u16),
+            max_chunks: DEFAULT_MAX_CHUNKS,
+            chunks: HashMap::new(),
+            states: HashMap::new(),
+            clock: 0,
+            cleared: 0,
+            is_lost: false,
+            offsets: Neighbourhood::Moore.offsets(Tiling::Square),
+        }
+    }
+
+    /// Returns the same board but which keeps at most `max_chunks` chunks
+    pub fn with_max_chunks(self, max_chunks: usize) -> Self {
+        Self {
+            max_chunks: max_chunks.max(MIN_MAX_CHUNKS),
+            ..self
+        }
+    }
+
+    /// Returns the cell by provided `position`,
+    ///  the chunk is generated if it's needed
+    pub fn cell(&mut self, position: InfinitePosition) -> InfiniteCell {
+        let (chunk_position, index) = position.locate();
+        let is_mine = self.chunk(chunk_position).mines[index];
+        let state = self
+            .states
+            .get(&position)
+            .copied()
+            .unwrap_or(CellState::Hidden);
+
+        let ctype = if is_mine {
+            CellType::Mine(1)
+        } else {
+            CellType::Empty(self.count_mines_around(position))
+        };
+
+        InfiniteCell {
+            position,
+            ctype,
+            state,
+        }
+    }
+
+    /// Reveals the cell by provided `position` and returns
+    ///  all revealed cells, empty cells without mines around
+    ///  reveal their neighbours up to `MAX_FLOOD_FILL` cells
+    ///
+    /// Does nothing if the game has been lost
+    ///  or the cell isn't hidden
+    pub fn reveal(&mut self, position: InfinitePosition) -> Vec<InfiniteCell> {
+        let cell = self.cell(position);
+
+        if self.is_lost || cell.state != CellState::Hidden {
+            return vec![];
+        }
+
+        if cell.ctype.is_mine() {
+            self.is_lost = true;
+            self.set_state(position, CellState::Exploded);
+
+            return vec![InfiniteCell {
+                state: CellState::Exploded,
+                ..cell
+            }];
+        }
+
+        let mut revealed = vec![];
+        let mut queue = VecDeque::new();
+
+        // Cells are revealed when they are queued,
+        //  so every cell is queued once
+        self.set_state(position, CellState::Revealed);
+        queue.push_back(position);
+
+        while let Some(position) = queue.pop_front() {
+            let cell = self.cell(position);
+            revealed.push(cell);
+
+            if cell.ctype != CellType::Empty(0) || revealed.len() + queue.len() >= MAX_FLOOD_FILL {
+                continue;
+            }
+
+            for neighbour in self.neighbours(position) {
+                if self.cell(neighbour).state == CellState::Hidden {
+                    self.set_state(neighbour, CellState::Revealed);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        self.cleared += revealed.len() as u64;
+
+        revealed
+    }
+
+    /// Flags or unflags the hidden cell by provided `position`
+    ///  and returns the actual cell
+    ///
+    /// The board has no edges, so flags are never run out
+    pub fn flag(&mut self, position: InfinitePosition) -> InfiniteCell {
+        let cell = self.cell(position);
+
+        let state = match cell.state {
+            _ if self.is_lost => return cell,
+            CellState::Hidden => CellState::Flagged(1),
+            CellState::Flagged(_) => CellState::Hidden,
+            _ => return cell,
+        };
+        self.set_state(position, state);
+
+        InfiniteCell { state, ..cell }
+    }
+
+    /// Returns how many cells have been revealed
+    pub fn score(&self) -> u64 {
+        self.cleared
+    }
+
+    /// Returns `true` if the player has hit a mine
+    pub fn is_lost(&self) -> bool {
+        self.is_lost
+    }
+
+    /// Returns how many mines every chunk contains
+    pub fn mines_per_chunk(&self) -> u16 {
+        self.mines_per_chunk
+    }
+
+    /// Returns the seed which chunks are generated from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns how many chunks are kept in memory now
+    pub fn chunks_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Returns how many cells aren't hidden,
+    ///  only their states are kept in memory
+    pub fn changed_cells_count(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Returns positions of all cells around the cell
+    fn neighbours(&self, position: InfinitePosition) -> Vec<InfinitePosition> {
+        self.offsets
+            .iter()
+            .map(|(x, y)| InfinitePosition {
+                x: position.x + *x as i64,
+                y: position.y + *y as i64,
+            })
+            .collect()
+    }
+
+    fn count_mines_around(&mut self, position: InfinitePosition) -> i8 {
+        let mut mines = 0;
+
+        for neighbour in self.neighbours(position) {
+            let (chunk_position, index) = neighbour.locate();
+
+            if self.chunk(chunk_position).mines[index] {
+                mines += 1;
+            }
+        }
+
+        mines
+    }
+
+    fn set_state(&mut self, position: InfinitePosition, state: CellState) {
+        if state == CellState::Hidden {
+            self.states.remove(&position);
+        } else {
+            self.states.insert(position, state);
+        }
+    }
+
+    /// Returns the chunk by provided `position` and generates it
+    ///  if it hasn't been generated yet or has been evicted
+    fn chunk(&mut self, position: ChunkPosition) -> &mut Chunk {
+        if !self.chunks.contains_key(&position) {
+            self.evict();
+        }
+
+        let (seed, mines) = (self.seed, self.mines_per_chunk);
+        let chunk = self
+            .chunks
+            .entry(position)
+            .or_insert_with(|| Self::generate_chunk(seed, mines, position));
+
+        self.clock += 1;
+        chunk.used_at = self.clock;
+
+        chunk
+    }
+
+    /// Drops the least recently used chunk if the store is full
+    fn evict(&mut self) {
+        if self.chunks.len() < self.max_chunks {
+            return;
+        }
+
+        let oldest = self
+            .chunks
+            .iter()
+            .min_by_key(|(_, chunk)| chunk.used_at)
+            .map(|(position, _)| *position);
+
+        if let Some(position) = oldest {
+            self.chunks.remove(&position);
+        }
+    }
+
+    /// Places `mines` in random cells of the chunk, the same `seed`
+    ///  and `position` always produce the same chunk
+    fn generate_chunk(seed: u64, mines: u16, position: ChunkPosition) -> Chunk {
+        // Mixes coordinates into the seed, so neighbour chunks
+        //  don't get similar seeds
+        let chunk_seed = seed
+            ^ (position.x as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+            ^ (position.y as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f);
+        let mut rng = StdRng::seed_from_u64(chunk_seed);
+
+        let mut cells = vec![false; CHUNK_CELLS];
+        for index in rand::seq::index::sample(&mut rng, CHUNK_CELLS, mines as usize) {
+            cells[index] = true;
+        }
+
+        Chunk {
+            mines: cells,
+            used_at: 0,
+        }
+    }
+}
+
+/// Returns positions of all mines of the chunk
+#[cfg(test)]
+fn chunk_mines(board: &mut InfiniteBoard, chunk: ChunkPosition) -> Vec<InfinitePosition> {
+    let size = CHUNK_SIZE as i64;
+    let mut mines = vec![];
+
+    for x in chunk.x * size..(chunk.x + 1) * size {
+        for y in chunk.y * size..(chunk.y + 1) * size {
+            let position = InfinitePosition { x, y };
+
+            if board.cell(position).ctype.is_mine() {
+                mines.push(position);
+            }
+        }
+    }
+
+    mines
+}
+
+#[test]
+fn should_place_the_same_count_of_mines_into_every_chunk() {
+    let mut board = InfiniteBoard::new(1, 40);
+
+    for (x, y) in [(0, 0), (-1, 0), (3, -7), (-100, 100)].iter() {
+        let mines = chunk_mines(&mut board, ChunkPosition { x: *x, y: *y });
+
+        assert_eq!(mines.len(), 40);
+    }
+}
+
+#[test]
+fn should_generate_chunks_from_seed() {
+    let chunk = ChunkPosition { x: -2, y: 5 };
+
+    let mines = chunk_mines(&mut InfiniteBoard::new(7, 30), chunk);
+
+    assert_eq!(mines, chunk_mines(&mut InfiniteBoard::new(7, 30), chunk));
+    assert_ne!(mines, chunk_mines(&mut InfiniteBoard::new(8, 30), chunk));
+    assert_ne!(
+        mines,
+        chunk_mines(
+            &mut InfiniteBoard::new(7, 30),
+            ChunkPosition { x: -2, y: 6 }
+        )
+    );
+}
+
+#[test]
+fn should_count_mines_across_chunk_borders() {
+    let mut board = InfiniteBoard::new(3, 60);
+    let size = CHUNK_SIZE as i64;
+
+    for x in -2..2 {
+        for y in size - 2..size + 2 {
+            let position = InfinitePosition { x, y };
+
+            if let CellType::Empty(value) = board.cell(position).ctype {
+                let mines = board
+                    .neighbours(position)
+                    .into_iter()
+                    .filter(|neighbour| board.cell(*neighbour).ctype.is_mine())
+                    .count();
+
+                assert_eq!(value as usize, mines);
+            }
+        }
+    }
+}
+
+#[test]
+fn should_reveal_openings_across_chunks_and_count_score() {
+    let mut board = InfiniteBoard::new(5, 20);
+
+    let opening = (0..CHUNK_SIZE as i64)
+        .map(|x| InfinitePosition { x, y: 0 })
+        .find(|position| board.cell(*position).ctype == CellType::Empty(0))
+        .unwrap();
+
+    let revealed = board.reveal(opening);
+
+    assert!(revealed.len() > 1);
+    assert!(revealed.iter().all(|cell| !cell.ctype.is_mine()));
+    assert!(revealed.iter().any(|cell| cell.position.y < 0));
+    assert_eq!(board.score(), revealed.len() as u64);
+
+    // Revealed cells are ignored
+    assert!(board.reveal(opening).is_empty());
+}
+
+#[test]
+fn should_stop_flood_fill_of_endless_openings() {
+    let mut board = InfiniteBoard::new(1, 0);
+
+    let revealed = board.reveal(InfinitePosition { x: 0, y: 0 });
+
+    assert!(revealed.len() >= MAX_FLOOD_FILL);
+    assert!(revealed.len() <= MAX_FLOOD_FILL + 8);
+}
+
+#[test]
+fn should_lose_when_the_mine_is_revealed() {
+    let mut board = InfiniteBoard::new(2, 50);
+    let mine = chunk_mines(&mut board, ChunkPosition { x: 0, y: 0 })[0];
+
+    let revealed = board.reveal(mine);
+
+    assert_eq!(revealed[0].state, CellState::Exploded);
+    assert!(board.is_lost());
+    assert_eq!(board.score(), 0);
+    assert!(board.reveal(InfinitePosition { x: 100, y: 100 }).is_empty());
+}
+
+#[test]
+fn should_flag_and_unflag_hidden_cells() {
+    let mut board = InfiniteBoard::new(2, 50);
+    let position = InfinitePosition { x: -5, y: 3 };
+
+    assert_eq!(board.flag(position).state, CellState::Flagged(1));
+    assert!(board.reveal(position).is_empty());
+    assert_eq!(board.flag(position).state, CellState::Hidden);
+}
+
+#[test]
+fn should_evict_chunks_and_keep_states_of_cells() {
+    let mut board = InfiniteBoard::new(4, 30).with_max_chunks(10);
+    let size = CHUNK_SIZE as i64;
+
+    let flagged = InfinitePosition { x: 1, y: 1 };
+    board.flag(flagged);
+    let mines = chunk_mines(&mut board, ChunkPosition { x: 5, y: 5 });
+
+    // Every flag touches another chunk
+    for x in 1..20 {
+        board.flag(InfinitePosition {
+            x: x * size * 3,
+            y: 0,
+        });
+    }
+
+    assert!(board.chunks_count() <= 10);
+    assert_eq!(board.changed_cells_count(), 20);
+    assert_eq!(board.cell(flagged).state, CellState::Flagged(1));
+
+    board.flag(flagged);
+
+    assert_eq!(board.changed_cells_count(), 19);
+
+    // Evicted chunks are generated again the same way
+    assert_eq!(chunk_mines(&mut board, ChunkPosition { x: 5, y: 5 }), mines);
+}
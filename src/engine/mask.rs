@@ -0,0 +1,122 @@
+/// The shape of the irregular battlefield: cells outside
+///  of the mask don't exist, they never hold mines
+///  and never count as neighbours
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mask {
+    rows: usize,
+    cols: usize,
+
+    /// `true` if the cell exists, indexed by `y * cols + x`
+    cells: Vec<bool>,
+}
+
+impl Mask {
+    /// Creates the mask from the ASCII pattern where every line is a row,
+    ///  `.` and spaces are cells which don't exist
+    ///  and any other character is a cell
+    ///
+    /// Lines shorter than the longest one are filled up with missed cells
+    ///
+    /// ```text
+    /// .##.##.
+    /// #######
+    /// .#####.
+    /// ..###..
+    /// ...#...
+    /// ```
+    pub fn from_ascii(pattern: &str) -> Result<Self, String> {
+        let lines = pattern
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .collect::<Vec<&str>>();
+
+        let rows = lines.len();
+        let cols = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut cells = vec![false; rows * cols];
+        for (y, line) in lines.iter().enumerate() {
+            for (x, char) in line.chars().enumerate() {
+                cells[y * cols + x] = char != '.' && char != ' ';
+            }
+        }
+
+        Self::from_cells(rows, cols, cells)
+    }
+
+    /// Creates the mask from the bitmap of `width x height` pixels
+    ///  stored row by row, where non-zero pixels are cells
+    pub fn from_bitmap(width: usize, height: usize, pixels: &[u8]) -> Result<Self, String> {
+        if pixels.len() != width * height {
+            return Err(format!(
+                "bitmap {}x{} must have {} pixels but has {}",
+                width,
+                height,
+                width * height,
+                pixels.len()
+            ));
+        }
+
+        let cells = pixels.iter().map(|pixel| *pixel != 0).collect();
+
+        Self::from_cells(height, width, cells)
+    }
+
+    /// Returns how many rows the mask has
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns how many columns the mask has
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns `true` if the cell on provided column `x` and row `y` exists
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.cols && y < self.rows && self.cells[y * self.cols + x]
+    }
+
+    /// Returns how many cells exist
+    pub fn cells_count(&self) -> usize {
+        self.cells.iter().filter(|cell| **cell).count()
+    }
+
+    fn from_cells(rows: usize, cols: usize, cells: Vec<bool>) -> Result<Self, String> {
+        let mask = Self { rows, cols, cells };
+
+        if mask.cells_count() == 0 {
+            return Err("mask must contain at least one cell".to_string());
+        }
+
+        Ok(mask)
+    }
+}
+
+#[test]
+fn should_parse_ascii_mask() {
+    let mask = Mask::from_ascii(".#.\n###\n#").unwrap();
+
+    assert_eq!((mask.rows(), mask.cols()), (3, 3));
+    assert_eq!(mask.cells_count(), 5);
+    assert!(mask.contains(1, 0));
+    assert!(!mask.contains(0, 0));
+    assert!(!mask.contains(2, 2));
+    assert!(!mask.contains(3, 1));
+}
+
+#[test]
+fn should_parse_bitmap_mask() {
+    let mask = Mask::from_bitmap(2, 3, &[1, 0, 255, 255, 0, 7]).unwrap();
+
+    assert_eq!((mask.rows(), mask.cols()), (3, 2));
+    assert!(mask.contains(0, 0));
+    assert!(!mask.contains(1, 0));
+    assert!(!mask.contains(0, 2));
+
+    assert!(Mask::from_bitmap(2, 2, &[1, 1, 1]).is_err());
+    assert!(Mask::from_bitmap(1, 1, &[0]).is_err());
+}
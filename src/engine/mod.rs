@@ -1,5 +1,28 @@
 mod battlefield;
 mod cell;
+mod config;
+mod editor;
+pub mod encoding;
+mod events;
+mod game;
+mod grid;
+mod infinite;
+mod mask;
+mod solver;
+mod topology;
+mod validation;
 
-pub use battlefield::BattleField;
+pub use battlefield::{BattleField, LossReport, Reveal};
 pub use cell::*;
+pub use config::{GameConfig, GuessMode, WinRule, MAX_CELLS, MAX_MINES_PER_CELL};
+pub use editor::BoardEditor;
+pub use events::{EventEmitter, GameEvent, SubscriptionId};
+pub use game::{Game, GameState, GameStatus};
+pub use grid::Grid;
+pub use infinite::{
+    ChunkPosition, InfiniteBoard, InfiniteCell, InfinitePosition, CHUNK_SIZE, MAX_FLOOD_FILL,
+};
+pub use mask::Mask;
+pub use solver::{Deduction, Solver};
+pub use topology::{Neighbourhood, Tiling, Topology, MAX_CUSTOM_OFFSET, MAX_CUSTOM_OFFSETS};
+pub use validation::{Counter, Violation};
@@ -0,0 +1,689 @@
+use std::collections::BTreeSet;
+
+use crate::engine::battlefield::BattleField;
+use crate::engine::cell::*;
+use crate::engine::grid::Grid;
+use crate::engine::topology::Topology;
+
+/// Cells which the solver could prove to be safe or to be mines
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Deduction {
+    /// Hidden cells which are safe to reveal
+    pub safe: Vec<CellId>,
+
+    /// Hidden cells which definitely contain mines
+    pub mines: Vec<CellId>,
+}
+
+/// How many assignments of cells `find_layout` may try
+///  before it gives up
+const MAX_LAYOUT_STEPS: usize = 100_000;
+
+/// What the layout search has found
+#[derive(Clone, Debug, PartialEq)]
+enum Layout {
+    /// Ids of unopened cells which should contain mines
+    Found(Vec<CellId>),
+
+    /// No layout is consistent with all clues
+    Impossible,
+
+    /// The search has given up or the battlefield isn't supported
+    Unknown,
+}
+
+/// A single clue of the revealed cell:
+///  exactly `mines` mines are placed among `cells`
+#[derive(Clone, Debug, PartialEq)]
+struct Constraint {
+    cells: BTreeSet<CellId>,
+    mines: usize,
+}
+
+/// Logical solver which looks at the battlefield the same way
+///  as the player does: it only uses values of revealed cells
+///  and never peeks at hidden ones
+///
+/// Flags are not trusted because the player may put them wrong
+pub struct Solver<'a, T: Topology = Grid> {
+    battlefield: &'a BattleField<T>,
+}
+
+impl<'a, T: Topology> Solver<'a, T> {
+    pub fn new(battlefield: &'a BattleField<T>) -> Self {
+        Self { battlefield }
+    }
+
+    /// Finds all cells which can be proved safe or mined
+    ///  by using single clues and pairs of clues where one
+    ///  clue covers a subset of cells of the other
+    ///
+    /// Clues of battlefields with negative mines don't tell how many
+    ///  mines are around, so nothing is deduced on them
+    pub fn deduce(&self) -> Deduction {
+        if self.battlefield.negative_bombs() > 0 {
+            return Deduction::default();
+        }
+
+        let constraints = self.constraints();
+
+        let mut safe = BTreeSet::new();
+        let mut mines = BTreeSet::new();
+
+        loop {
+            let constraints = constraints
+                .iter()
+                .filter_map(|constraint| self.reduce(constraint, &safe, &mines))
+                .collect::<Vec<Constraint>>();
+
+            let mut changed = false;
+
+            for constraint in constraints.iter() {
+                changed |= self.apply(constraint, &mut safe, &mut mines);
+            }
+
+            // If one clue covers a subset of cells of another clue,
+            //  the rest of cells of the bigger one contains
+            //  the difference of their mines
+            for subset in constraints.iter() {
+                for superset in constraints.iter() {
+                    if subset == superset
+                        || subset.mines > superset.mines
+                        || !subset.cells.is_subset(&superset.cells)
+                    {
+                        continue;
+                    }
+
+                    let difference = Constraint {
+                        cells: superset.cells.difference(&subset.cells).copied().collect(),
+                        mines: superset.mines - subset.mines,
+                    };
+
+                    changed |= self.apply(&difference, &mut safe, &mut mines);
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        Deduction {
+            safe: safe.into_iter().collect(),
+            mines: mines.into_iter().collect(),
+        }
+    }
+
+    /// Finds a layout of mines among unopened cells which is consistent
+    ///  with all clues and the count of mines, where the cell with `cell_id`
+    ///  contains a mine or not as requested by `is_mine`
+    ///
+    /// Unlike `deduce` it looks at the current layout, so the first
+    ///  consistent layout found is as close to it as possible.
+    ///  Returns ids of all unopened cells which should contain mines
+    ///  or `None` if the layout doesn't exist or hasn't been found
+    ///  in `MAX_LAYOUT_STEPS` steps
+    ///
+    /// Only battlefields where every cell contains a single mine
+    ///  and there are no negative mines are supported
+    pub fn find_layout(&self, cell_id: CellId, is_mine: bool) -> Option<Vec<CellId>> {
+        match self.search_layout(cell_id, is_mine) {
+            Layout::Found(layout) => Some(layout),
+            Layout::Impossible | Layout::Unknown => None,
+        }
+    }
+
+    /// Returns `true` if some unopened cell is proved safe:
+    ///  no layout consistent with all clues and the count of mines
+    ///  puts a mine into it
+    ///
+    /// It finds everything `deduce` finds and also cells which are safe
+    ///  by the count of mines or by longer chains of clues. Cells for which
+    ///  the search gives up after `MAX_LAYOUT_STEPS` steps aren't proved
+    pub fn has_safe_cell(&self) -> bool {
+        if !self.deduce().safe.is_empty() {
+            return true;
+        }
+
+        let frontier = self
+            .constraints()
+            .iter()
+            .flat_map(|constraint| constraint.cells.iter().copied())
+            .collect::<BTreeSet<CellId>>();
+
+        // Cells which no clue sees are all alike,
+        //  so it's enough to check one of them
+        let interior = self
+            .battlefield
+            .get_all()
+            .iter()
+            .flatten()
+            .find(|cell| cell.state.is_unopened() && !frontier.contains(&cell.id))
+            .map(|cell| cell.id);
+
+        frontier
+            .iter()
+            .copied()
+            .chain(interior)
+            .any(|cell_id| self.search_layout(cell_id, true) == Layout::Impossible)
+    }
+
+    /// Searches the layout for `find_layout` and tells apart layouts
+    ///  which don't exist from the search which has given up
+    fn search_layout(&self, cell_id: CellId, is_mine: bool) -> Layout {
+        if self.battlefield.negative_bombs() > 0 || self.battlefield.max_mines_per_cell() > 1 {
+            return Layout::Unknown;
+        }
+
+        let cells = self.battlefield.get_all().iter().flatten();
+        let unopened = cells
+            .clone()
+            .filter(|cell| cell.state.is_unopened())
+            .collect::<Vec<&Cell>>();
+
+        if !unopened.iter().any(|cell| cell.id == cell_id) {
+            return Layout::Unknown;
+        }
+
+        let exploded_mines = cells
+            .filter(|cell| cell.state == CellState::Exploded)
+            .map(|cell| cell.ctype.mines() as usize)
+            .sum::<usize>();
+        let mines = match (self.battlefield.bombs() as usize).checked_sub(exploded_mines) {
+            Some(mines) => mines,
+            None => return Layout::Unknown,
+        };
+
+        let constraints = self.constraints();
+        let frontier = constraints
+            .iter()
+            .flat_map(|constraint| constraint.cells.iter().copied())
+            .collect::<BTreeSet<CellId>>();
+
+        // Cells which no clue sees may take any mines
+        //  which are left after the frontier
+        let interior = unopened
+            .iter()
+            .filter(|cell| !frontier.contains(&cell.id))
+            .collect::<Vec<_>>();
+        let is_interior = !frontier.contains(&cell_id);
+        let interior_mines = if is_interior && is_mine { 1 } else { 0 };
+        let interior_capacity = if is_interior && !is_mine {
+            interior.len() - 1
+        } else {
+            interior.len()
+        };
+
+        let mut search = LayoutSearch::new(&constraints, frontier.iter().copied().collect());
+        search.total_mines = mines;
+        search.interior = interior_mines..=interior_capacity;
+        search.preferred = search
+            .cells
+            .iter()
+            .map(|id| self.battlefield.get(*id).ctype.is_mine())
+            .collect();
+        search.forced = search
+            .cells
+            .iter()
+            .position(|id| *id == cell_id)
+            .map(|index| (index, is_mine));
+
+        if !search.run(0) {
+            return if search.steps > MAX_LAYOUT_STEPS {
+                Layout::Unknown
+            } else {
+                Layout::Impossible
+            };
+        }
+
+        let mut layout = search
+            .cells
+            .iter()
+            .zip(search.assigned.iter())
+            .filter(|(_, is_mined)| **is_mined)
+            .map(|(id, _)| *id)
+            .collect::<Vec<CellId>>();
+
+        // Mines of interior cells stay where they are if possible
+        let mut interior_left = mines - search.assigned_mines;
+        let mut candidates = interior
+            .iter()
+            .filter(|cell| cell.id != cell_id)
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|cell| !cell.ctype.is_mine());
+
+        if is_interior && is_mine {
+            layout.push(cell_id);
+            interior_left -= 1;
+        }
+
+        layout.extend(candidates.iter().take(interior_left).map(|cell| cell.id));
+        layout.sort_unstable();
+
+        Layout::Found(layout)
+    }
+
+    /// Collects clues of all revealed cells which have
+    ///  not revealed cells around
+    fn constraints(&self) -> Vec<Constraint> {
+        let mut constraints = vec![];
+
+        for cell in self.battlefield.get_all().iter().flatten() {
+            let value = match (cell.state, cell.ctype) {
+                (CellState::Revealed, CellType::Empty(value)) => value,
+                _ => continue,
+            };
+
+            let neighbours = self.battlefield.neighbours(cell.position);
+
+            // Exploded mines are known to the player,
+            //  so they are taken out of the clue
+            let exploded_mines = neighbours
+                .iter()
+                .filter(|neighbour| neighbour.state == CellState::Exploded)
+                .map(|neighbour| neighbour.ctype.mines() as usize)
+                .sum::<usize>();
+            let cells = neighbours
+                .iter()
+                .filter(|neighbour| {
+                    neighbour.state != CellState::Revealed && neighbour.state != CellState::Exploded
+                })
+                .map(|neighbour| neighbour.id)
+                .collect::<BTreeSet<CellId>>();
+
+            if !cells.is_empty() {
+                constraints.push(Constraint {
+                    cells,
+                    mines: (value as usize).saturating_sub(exploded_mines),
+                });
+            }
+        }
+
+        constraints
+    }
+
+    /// Removes already known cells from the constraint
+    ///
+    /// Mined cells are removed only if every cell contains
+    ///  a single mine, otherwise it's unknown how many mines
+    ///  of the constraint they take
+    fn reduce(
+        &self,
+        constraint: &Constraint,
+        safe: &BTreeSet<CellId>,
+        mines: &BTreeSet<CellId>,
+    ) -> Option<Constraint> {
+        let is_single_mine = self.battlefield.max_mines_per_cell() == 1;
+        let known_mines = if is_single_mine {
+            constraint.cells.intersection(mines).count()
+        } else {
+            0
+        };
+        let cells = constraint
+            .cells
+            .iter()
+            .filter(|cell_id| {
+                let is_known = safe.contains(cell_id) || is_single_mine && mines.contains(cell_id);
+
+                !is_known
+            })
+            .copied()
+            .collect::<BTreeSet<CellId>>();
+
+        if cells.is_empty() {
+            None
+        } else {
+            Some(Constraint {
+                cells,
+                mines: constraint.mines.saturating_sub(known_mines),
+            })
+        }
+    }
+
+    /// Marks all cells of the constraint as safe if it has no mines
+    ///  or as mines if every cell must contain a mine, which happens
+    ///  when the rest of cells can't hold all mines of the constraint
+    ///
+    /// Returns `true` if something new has been found
+    fn apply(
+        &self,
+        constraint: &Constraint,
+        safe: &mut BTreeSet<CellId>,
+        mines: &mut BTreeSet<CellId>,
+    ) -> bool {
+        let max_mines_per_cell = self.battlefield.max_mines_per_cell() as usize;
+        let mut changed = false;
+
+        if constraint.mines == 0 {
+            for cell_id in constraint.cells.iter() {
+                changed |= safe.insert(*cell_id);
+            }
+        } else if constraint.mines > constraint.cells.len().saturating_sub(1) * max_mines_per_cell {
+            for cell_id in constraint.cells.iter() {
+                changed |= mines.insert(*cell_id);
+            }
+        }
+
+        changed
+    }
+}
+
+/// Backtracking search which assigns a mine or no mine
+///  to every cell seen by clues, so all clues are satisfied
+struct LayoutSearch {
+    /// Cells to assign in the order of the search
+    cells: Vec<CellId>,
+
+    /// Indices of constraints which every cell belongs to
+    constraints_of: Vec<Vec<usize>>,
+
+    /// Values which are tried first for every cell
+    preferred: Vec<bool>,
+
+    /// The cell which may get the only value
+    forced: Option<(usize, bool)>,
+
+    /// Current values of assigned cells
+    assigned: Vec<bool>,
+
+    /// How many mines every constraint still needs
+    mines_left: Vec<isize>,
+
+    /// How many cells of every constraint aren't assigned yet
+    cells_left: Vec<usize>,
+
+    /// How many mines should be placed on the whole map
+    total_mines: usize,
+
+    /// How many mines have been assigned
+    assigned_mines: usize,
+
+    /// How many mines cells which aren't seen by clues may take
+    interior: std::ops::RangeInclusive<usize>,
+
+    steps: usize,
+}
+
+impl LayoutSearch {
+    fn new(constraints: &[Constraint], cells: Vec<CellId>) -> Self {
+        let constraints_of = cells
+            .iter()
+            .map(|cell_id| {
+                constraints
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, constraint)| constraint.cells.contains(cell_id))
+                    .map(|(index, _)| index)
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            assigned: vec![false; cells.len()],
+            preferred: vec![false; cells.len()],
+            cells,
+            constraints_of,
+            forced: None,
+            mines_left: constraints.iter().map(|c| c.mines as isize).collect(),
+            cells_left: constraints.iter().map(|c| c.cells.len()).collect(),
+            total_mines: 0,
+            assigned_mines: 0,
+            interior: 0..=0,
+            steps: 0,
+        }
+    }
+
+    /// Assigns cells starting from `index` and returns `true`
+    ///  if all of them have been assigned
+    fn run(&mut self, index: usize) -> bool {
+        self.steps += 1;
+
+        // Mines which are left for unassigned cells and interior ones
+        //  should fit into them
+        let mines_left = self.total_mines as isize - self.assigned_mines as isize;
+        let cells_left = (self.cells.len() - index) as isize;
+
+        if self.steps > MAX_LAYOUT_STEPS
+            || mines_left < *self.interior.start() as isize
+            || mines_left > cells_left + *self.interior.end() as isize
+        {
+            return false;
+        }
+
+        if index == self.cells.len() {
+            return self.interior.contains(&(mines_left as usize));
+        }
+
+        let values = match self.forced {
+            Some((forced, value)) if forced == index => vec![value],
+            _ => vec![self.preferred[index], !self.preferred[index]],
+        };
+
+        for value in values {
+            let is_valid = self.assign(index, value);
+
+            if is_valid && self.run(index + 1) {
+                return true;
+            }
+
+            self.unassign(index, value);
+        }
+
+        false
+    }
+
+    /// Assigns the value to the cell and returns `false`
+    ///  if one of its constraints can't be satisfied anymore
+    fn assign(&mut self, index: usize, is_mine: bool) -> bool {
+        self.assigned[index] = is_mine;
+        self.assigned_mines += is_mine as usize;
+
+        let mut is_valid = true;
+
+        for constraint in self.constraints_of[index].iter() {
+            self.cells_left[*constraint] -= 1;
+            self.mines_left[*constraint] -= is_mine as isize;
+
+            let mines_left = self.mines_left[*constraint];
+            is_valid &= mines_left >= 0 && mines_left <= self.cells_left[*constraint] as isize;
+        }
+
+        is_valid
+    }
+
+    fn unassign(&mut self, index: usize, is_mine: bool) {
+        self.assigned[index] = false;
+        self.assigned_mines -= is_mine as usize;
+
+        for constraint in self.constraints_of[index].iter() {
+            self.cells_left[*constraint] += 1;
+            self.mines_left[*constraint] += is_mine as isize;
+        }
+    }
+}
+
+/// Reveals provided cells on the battlefield
+#[cfg(test)]
+fn reveal_all(battlefield: &mut BattleField, cells: &[CellId]) {
+    for cell_id in cells {
+        battlefield.reveal(*cell_id);
+    }
+}
+
+#[test]
+fn should_not_deduce_anything_on_hidden_battlefield() {
+    let battlefield = BattleField::with_seed(5, 5, 5, 1);
+
+    assert_eq!(Solver::new(&battlefield).deduce(), Deduction::default());
+}
+
+#[test]
+fn should_only_deduce_correct_cells() {
+    for seed in 0..20 {
+        let mut battlefield = BattleField::with_seed(8, 8, 10, seed);
+
+        let safe_cell = battlefield
+            .get_all()
+            .iter()
+            .flatten()
+            .find(|cell| cell.ctype == CellType::Empty(0))
+            .map(|cell| cell.id);
+
+        if let Some(cell_id) = safe_cell {
+            reveal_all(&mut battlefield, &[cell_id]);
+        }
+
+        let deduction = Solver::new(&battlefield).deduce();
+
+        for cell_id in deduction.safe {
+            assert_ne!(battlefield.get(cell_id).ctype, CellType::Mine(1));
+        }
+
+        for cell_id in deduction.mines {
+            assert_eq!(battlefield.get(cell_id).ctype, CellType::Mine(1));
+        }
+    }
+}
+
+#[test]
+fn should_deduce_the_mine_by_single_clue() {
+    // |1, b|
+    let mut battlefield = BattleField::with_seed(1, 2, 0, 0);
+    battlefield.get_mut(0).ctype = CellType::Empty(1);
+    battlefield.get_mut(1).ctype = CellType::Mine(1);
+
+    reveal_all(&mut battlefield, &[0]);
+
+    let deduction = Solver::new(&battlefield).deduce();
+
+    assert_eq!(deduction.mines, vec![1]);
+    assert!(deduction.safe.is_empty());
+}
+
+#[test]
+fn should_use_hex_neighbours_for_constraints() {
+    use crate::engine::{GameConfig, Tiling};
+
+    let config = GameConfig::new(6, 6, 6)
+        .with_seed(11)
+        .with_tiling(Tiling::Hex);
+    let mut battlefield = BattleField::from_config(&config);
+
+    let safe_cell = battlefield
+        .get_all()
+        .iter()
+        .flatten()
+        .find(|cell| cell.ctype == CellType::Empty(0))
+        .map(|cell| cell.id)
+        .unwrap();
+    battlefield.reveal(safe_cell);
+
+    let deduction = Solver::new(&battlefield).deduce();
+
+    for cell_id in deduction.safe {
+        assert_ne!(battlefield.get(cell_id).ctype, CellType::Mine(1));
+    }
+    for cell_id in deduction.mines {
+        assert_eq!(battlefield.get(cell_id).ctype, CellType::Mine(1));
+    }
+}
+
+#[test]
+fn should_use_neighbours_across_edges_of_wrapped_map() {
+    use crate::engine::GameConfig;
+
+    let config = GameConfig::new(6, 6, 5).with_seed(2).with_wrap(true);
+    let mut battlefield = BattleField::from_config(&config);
+
+    let safe_cell = battlefield
+        .get_all()
+        .iter()
+        .flatten()
+        .find(|cell| cell.ctype == CellType::Empty(0))
+        .map(|cell| cell.id)
+        .unwrap();
+    battlefield.reveal(safe_cell);
+
+    let deduction = Solver::new(&battlefield).deduce();
+
+    for cell_id in deduction.safe {
+        assert_ne!(battlefield.get(cell_id).ctype, CellType::Mine(1));
+    }
+    for cell_id in deduction.mines {
+        assert_eq!(battlefield.get(cell_id).ctype, CellType::Mine(1));
+    }
+}
+
+#[test]
+fn should_find_layout_consistent_with_clues() {
+    // |1, b, 1|
+    let mut battlefield = BattleField::with_map(vec![
+        vec![Cell::new(
+            0,
+            CellType::Empty(1),
+            CellPosition { x: 0, y: 0 },
+        )],
+        vec![Cell::new(1, CellType::Mine(1), CellPosition { x: 1, y: 0 })],
+        vec![Cell::new(
+            2,
+            CellType::Empty(1),
+            CellPosition { x: 2, y: 0 },
+        )],
+    ]);
+
+    let solver = Solver::new(&battlefield);
+
+    assert_eq!(solver.find_layout(1, false), Some(vec![0]));
+    assert_eq!(solver.find_layout(2, true), Some(vec![2]));
+
+    reveal_all(&mut battlefield, &[0]);
+
+    let solver = Solver::new(&battlefield);
+
+    // The clue requires the mine next to it and
+    //  the only mine can't be anywhere else
+    assert_eq!(solver.find_layout(1, false), None);
+    assert_eq!(solver.find_layout(2, true), None);
+    assert_eq!(solver.find_layout(2, false), Some(vec![1]));
+
+    // Revealed cells can't get mines
+    assert_eq!(solver.find_layout(0, true), None);
+}
+
+#[test]
+fn should_find_cells_which_are_safe_by_the_count_of_mines() {
+    // |1, b, 1, 0, 0| and |1, b, 1, 1, b|
+    let row = |mines: &[i16]| {
+        let mut battlefield = BattleField::with_map(
+            (0..5)
+                .map(|x| {
+                    let ctype = if mines.contains(&x) {
+                        CellType::Mine(1)
+                    } else {
+                        let around = mines.iter().filter(|mine| (**mine - x).abs() == 1);
+                        CellType::Empty(around.count() as i8)
+                    };
+
+                    vec![Cell::new(x as CellId, ctype, CellPosition { x, y: 0 })]
+                })
+                .collect(),
+        );
+        reveal_all(&mut battlefield, &[2]);
+
+        battlefield
+    };
+
+    let single_mine = row(&[1]);
+    let solver = Solver::new(&single_mine);
+
+    // The only mine is next to the revealed cell,
+    //  so the cells at the edges are safe
+    assert!(solver.deduce().safe.is_empty());
+    assert!(solver.has_safe_cell());
+
+    let two_mines = row(&[1, 4]);
+    let solver = Solver::new(&two_mines);
+
+    assert!(solver.deduce().safe.is_empty());
+    assert!(!solver.has_safe_cell());
+}
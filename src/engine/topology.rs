@@ -0,0 +1,352 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::engine::cell::CellPosition;
+
+/// Square cells touch their 8 neighbours by sides and corners
+const SQUARE_OFFSETS: [(i16, i16); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// Square cells which touch the cell by sides
+const VON_NEUMANN_OFFSETS: [(i16, i16); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Square cells which are reachable by the chess knight
+const KNIGHT_OFFSETS: [(i16, i16); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+
+/// Hexagonal cells in axial coordinates touch 6 neighbours
+const HEX_OFFSETS: [(i16, i16); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+/// Hexagonal cells which are reachable by the knight of the hexagonal chess
+const HEX_KNIGHT_OFFSETS: [(i16, i16); 12] = [
+    (1, 2),
+    (2, 1),
+    (3, -1),
+    (3, -2),
+    (2, -3),
+    (1, -3),
+    (-1, -2),
+    (-2, -1),
+    (-3, 1),
+    (-3, 2),
+    (-2, 3),
+    (-1, 3),
+];
+
+/// Geometry of the battlefield: which cells exist
+///  and which of them are neighbours
+///
+/// Cells are stored in the `width x height` map and the battlefield
+///  does everything else by itself: places bombs, counts values,
+///  reveals, chords and flags cells, and the solver deduces them,
+///  so a new geometry needs only to implement this trait
+///
+/// `Grid` is the geometry of all built-in battlefields
+pub trait Topology {
+    /// How many columns the map has
+    fn width(&self) -> usize;
+
+    /// How many rows the map has
+    fn height(&self) -> usize;
+
+    /// Returns `false` if the cell on provided `position` doesn't exist,
+    ///  such cells never hold mines and never count as neighbours
+    fn contains(&self, _position: CellPosition) -> bool {
+        true
+    }
+
+    /// Returns positions of all cells which are counted by the value
+    ///  of the cell on provided `position`
+    ///
+    /// The position itself, duplicates, positions outside of the map
+    ///  and positions of cells which don't exist are ignored
+    fn neighbours(&self, position: CellPosition) -> Vec<CellPosition>;
+}
+
+/// The shape of cells
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Tiling {
+    /// The classic grid of square cells with 8 neighbours
+    #[default]
+    Square,
+
+    /// The grid of hexagonal cells with 6 neighbours
+    ///
+    /// Positions are axial coordinates where `x` is `q` and `y` is `r`,
+    ///  so `cols x rows` map is drawn as a parallelogram
+    ///  where every next row is shifted by half of the cell
+    Hex,
+}
+
+/// Cells which are counted by the value of the empty cell
+///
+/// Every neighbourhood is applied to the grid of the `Tiling`,
+///  so the same neighbourhood may have different offsets on
+///  square and hexagonal maps
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Neighbourhood {
+    /// All cells which touch the cell by sides or corners
+    #[default]
+    Moore,
+
+    /// Only cells which touch the cell by sides
+    ///
+    /// Hexagonal cells have no corner neighbours,
+    ///  so it's the same as `Moore` on hexagonal maps
+    VonNeumann,
+
+    /// Cells which are reachable by one move of the chess knight
+    Knight,
+
+    /// All cells which are at most 2 steps away
+    Radius2,
+
+    /// Arbitrary offsets of cells which are counted by the cell
+    ///
+    /// Offsets may be not symmetric, in that case the cell
+    ///  may count the mine which doesn't count the cell back
+    ///
+    /// The cell itself, duplicated offsets and offsets farther
+    ///  than `MAX_CUSTOM_OFFSET` are ignored, only the first
+    ///  `MAX_CUSTOM_OFFSETS` offsets are used because values
+    ///  of cells are stored in `i8`, use `Neighbourhood::custom`
+    ///  to get the error instead
+    Custom(Vec<(i16, i16)>),
+}
+
+/// How many offsets the custom neighbourhood may have,
+///  cells of maps with several layers have `3 * 40 + 2` neighbours
+///  at most, so values of cells always fit `i8`
+pub const MAX_CUSTOM_OFFSETS: usize = 40;
+
+/// How far the custom neighbour may be from the cell along each axis
+pub const MAX_CUSTOM_OFFSET: i16 = 255;
+
+impl Neighbourhood {
+    /// Creates the custom neighbourhood of provided `offsets`
+    ///  or returns the error if any of them is farther than
+    ///  `MAX_CUSTOM_OFFSET` or there are more than `MAX_CUSTOM_OFFSETS`
+    ///  of them besides the cell itself and duplicates
+    pub fn custom(offsets: Vec<(i16, i16)>) -> Result<Self, String> {
+        if let Some((x, y)) = offsets.iter().find(|offset| !Self::is_near(offset)) {
+            return Err(format!(
+                "custom neighbour ({}, {}) is farther than {} from the cell",
+                x, y, MAX_CUSTOM_OFFSET
+            ));
+        }
+
+        if Self::unique_offsets(&offsets).len() > MAX_CUSTOM_OFFSETS {
+            return Err(format!(
+                "custom neighbourhood may have at most {} offsets",
+                MAX_CUSTOM_OFFSETS
+            ));
+        }
+
+        Ok(Neighbourhood::Custom(offsets))
+    }
+
+    /// Returns offsets of all neighbours from the cell on the grid
+    ///  of provided `tiling`
+    pub fn offsets(&self, tiling: Tiling) -> Vec<(i16, i16)> {
+        match (self, tiling) {
+            (Neighbourhood::Moore, Tiling::Square) => SQUARE_OFFSETS.to_vec(),
+            (Neighbourhood::VonNeumann, Tiling::Square) => VON_NEUMANN_OFFSETS.to_vec(),
+            (Neighbourhood::Knight, Tiling::Square) => KNIGHT_OFFSETS.to_vec(),
+            (Neighbourhood::Moore, Tiling::Hex) | (Neighbourhood::VonNeumann, Tiling::Hex) => {
+                HEX_OFFSETS.to_vec()
+            }
+            (Neighbourhood::Knight, Tiling::Hex) => HEX_KNIGHT_OFFSETS.to_vec(),
+            (Neighbourhood::Radius2, _) => {
+                let mut offsets = vec![];
+
+                for x in -2..=2_i16 {
+                    for y in -2..=2_i16 {
+                        // Axial coordinates have the third implicit
+                        //  axis which must fit the radius too
+                        let is_inside = tiling == Tiling::Square || (x + y).abs() <= 2;
+
+                        if (x, y) != (0, 0) && is_inside {
+                            offsets.push((x, y));
+                        }
+                    }
+                }
+
+                offsets
+            }
+            (Neighbourhood::Custom(custom_offsets), _) => {
+                let mut offsets = Self::unique_offsets(custom_offsets);
+                offsets.truncate(MAX_CUSTOM_OFFSETS);
+
+                offsets
+            }
+        }
+    }
+
+    /// Returns custom offsets without the cell itself, duplicates
+    ///  and offsets which are too far
+    fn unique_offsets(custom_offsets: &[(i16, i16)]) -> Vec<(i16, i16)> {
+        let mut offsets = Vec::with_capacity(custom_offsets.len());
+
+        for offset in custom_offsets {
+            if *offset != (0, 0) && Self::is_near(offset) && !offsets.contains(offset) {
+                offsets.push(*offset);
+            }
+        }
+
+        offsets
+    }
+
+    /// Returns `true` if the offset isn't farther
+    ///  than `MAX_CUSTOM_OFFSET` along both axes
+    fn is_near((x, y): &(i16, i16)) -> bool {
+        let range = -MAX_CUSTOM_OFFSET..=MAX_CUSTOM_OFFSET;
+
+        range.contains(x) && range.contains(y)
+    }
+}
+
+/// Formats the neighbourhood the way it's parsed back by `FromStr`,
+///  so it may be recorded in saved games
+///
+/// Custom offsets are written as `custom:x,y;x,y`
+impl fmt::Display for Neighbourhood {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Neighbourhood::Moore => write!(f, "moore"),
+            Neighbourhood::VonNeumann => write!(f, "von-neumann"),
+            Neighbourhood::Knight => write!(f, "knight"),
+            Neighbourhood::Radius2 => write!(f, "radius-2"),
+            Neighbourhood::Custom(offsets) => {
+                let offsets = offsets
+                    .iter()
+                    .map(|(x, y)| format!("{},{}", x, y))
+                    .collect::<Vec<String>>();
+
+                write!(f, "custom:{}", offsets.join(";"))
+            }
+        }
+    }
+}
+
+impl FromStr for Neighbourhood {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "moore" => Ok(Neighbourhood::Moore),
+            "von-neumann" => Ok(Neighbourhood::VonNeumann),
+            "knight" => Ok(Neighbourhood::Knight),
+            "radius-2" => Ok(Neighbourhood::Radius2),
+            _ => {
+                let offsets = value
+                    .strip_prefix("custom:")
+                    .ok_or_else(|| format!("unknown neighbourhood: {}", value))?;
+
+                offsets
+                    .split(';')
+                    .filter(|offset| !offset.is_empty())
+                    .map(|offset| {
+                        let mut coordinates = offset
+                            .split(',')
+                            .map(|coordinate| coordinate.trim().parse::<i16>().ok());
+
+                        match (coordinates.next(), coordinates.next(), coordinates.next()) {
+                            (Some(Some(x)), Some(Some(y)), None) => Ok((x, y)),
+                            _ => Err(format!("invalid neighbourhood offset: {}", offset)),
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .and_then(Neighbourhood::custom)
+            }
+        }
+    }
+}
+
+#[test]
+fn should_count_offsets_of_every_neighbourhood() {
+    let counts = [
+        (Neighbourhood::Moore, 8, 6),
+        (Neighbourhood::VonNeumann, 4, 6),
+        (Neighbourhood::Knight, 8, 12),
+        (Neighbourhood::Radius2, 24, 18),
+        (Neighbourhood::Custom(vec![(0, 0), (1, 0), (1, 0)]), 1, 1),
+    ];
+
+    for (neighbourhood, square, hex) in counts.iter() {
+        assert_eq!(neighbourhood.offsets(Tiling::Square).len(), *square);
+        assert_eq!(neighbourhood.offsets(Tiling::Hex).len(), *hex);
+    }
+}
+
+#[test]
+fn should_parse_formatted_neighbourhood() {
+    let neighbourhoods = [
+        Neighbourhood::Moore,
+        Neighbourhood::VonNeumann,
+        Neighbourhood::Knight,
+        Neighbourhood::Radius2,
+        Neighbourhood::Custom(vec![(2, 0), (-2, 0), (0, -3)]),
+    ];
+
+    for neighbourhood in neighbourhoods.iter() {
+        assert_eq!(
+            neighbourhood.to_string().parse::<Neighbourhood>(),
+            Ok(neighbourhood.clone())
+        );
+    }
+
+    assert!("custom:1".parse::<Neighbourhood>().is_err());
+    assert!("queen".parse::<Neighbourhood>().is_err());
+}
+
+#[test]
+fn should_limit_the_count_of_custom_offsets() {
+    let offsets = (1..=MAX_CUSTOM_OFFSETS as i16 + 1)
+        .map(|x| (x, 0))
+        .collect::<Vec<(i16, i16)>>();
+    let mut allowed = offsets[..MAX_CUSTOM_OFFSETS].to_vec();
+    allowed.extend_from_slice(&[(0, 0), (1, 0)]);
+
+    assert!(Neighbourhood::custom(offsets.clone()).is_err());
+    assert!(Neighbourhood::custom(allowed).is_ok());
+    assert_eq!(
+        Neighbourhood::Custom(offsets.clone())
+            .offsets(Tiling::Square)
+            .len(),
+        MAX_CUSTOM_OFFSETS
+    );
+
+    let custom = Neighbourhood::Custom(offsets).to_string();
+
+    assert!(custom.parse::<Neighbourhood>().is_err());
+}
+
+#[test]
+fn should_reject_custom_offsets_which_are_too_far() {
+    let far = MAX_CUSTOM_OFFSET + 1;
+
+    assert!(Neighbourhood::custom(vec![(MAX_CUSTOM_OFFSET, -MAX_CUSTOM_OFFSET)]).is_ok());
+    assert!(Neighbourhood::custom(vec![(1, 0), (far, 0)]).is_err());
+    assert!(Neighbourhood::custom(vec![(0, i16::MIN)]).is_err());
+    assert!("custom:32767,0".parse::<Neighbourhood>().is_err());
+    assert_eq!(
+        Neighbourhood::Custom(vec![(i16::MAX, 0), (1, 0)]).offsets(Tiling::Square),
+        vec![(1, 0)]
+    );
+}
@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::engine::battlefield::BattleField;
+use crate::engine::cell::*;
+use crate::engine::topology::Topology;
+
+/// Counters which the battlefield keeps besides its cells
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Counter {
+    Bombs,
+    NegativeBombs,
+    FlagsLeft,
+}
+
+impl fmt::Display for Counter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Counter::Bombs => "bombs",
+            Counter::NegativeBombs => "negative bombs",
+            Counter::FlagsLeft => "flags left",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Inconsistency of the battlefield found by `BattleField::validate`
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    /// The value of the empty cell isn't the sum of mines around it
+    WrongValue {
+        id: CellId,
+        value: i8,
+        expected: i16,
+    },
+
+    /// Several cells have the same id
+    DuplicateId { id: CellId, count: usize },
+
+    /// No cell has the id, but ids must go from `0`
+    ///  up to the count of cells without gaps
+    MissingId { id: CellId },
+
+    /// The position of the cell isn't its place in the map
+    WrongPosition {
+        id: CellId,
+        position: CellPosition,
+        expected: CellPosition,
+    },
+
+    /// The cell contains more mines than any cell may contain
+    TooManyMines { id: CellId, mines: u8, max: u8 },
+
+    /// The counter doesn't match mines and flags of cells
+    WrongCounter {
+        counter: Counter,
+        value: u16,
+        expected: u16,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::WrongValue {
+                id,
+                value,
+                expected,
+            } => write!(
+                f,
+                "cell {} has value {} but {} mines are around it",
+                id, value, expected
+            ),
+            Violation::DuplicateId { id, count } => {
+                write!(f, "id {} is used by {} cells", id, count)
+            }
+            Violation::MissingId { id } => write!(f, "no cell has id {}", id),
+            Violation::WrongPosition {
+                id,
+                position,
+                expected,
+            } => write!(
+                f,
+                "cell {} has position ({}, {}) but it's placed at ({}, {})",
+                id, position.x, position.y, expected.x, expected.y
+            ),
+            Violation::TooManyMines { id, mines, max } => write!(
+                f,
+                "cell {} contains {} mines but at most {} are allowed",
+                id, mines, max
+            ),
+            Violation::WrongCounter {
+                counter,
+                value,
+                expected,
+            } => write!(
+                f,
+                "{} counter is {} but cells have {}",
+                counter, value, expected
+            ),
+        }
+    }
+}
+
+impl<T: Topology> BattleField<T> {
+    /// Checks that cells and counters of the battlefield agree with
+    ///  each other and returns every violation found, so the map
+    ///  is consistent if nothing is returned
+    ///
+    /// Values of empty cells are checked against mines around them,
+    ///  ids must be unique and go without gaps, positions must match
+    ///  places of cells in the map, and `bombs`, `negative_bombs`
+    ///  and `flags_left` must match mines and flags of cells
+    pub fn validate(&self) -> Vec<Violation> {
+        let mut violations = vec![];
+
+        self.validate_ids(&mut violations);
+        self.validate_cells(&mut violations);
+        self.validate_counters(&mut violations);
+
+        violations
+    }
+
+    fn validate_ids(&self, violations: &mut Vec<Violation>) {
+        let mut ids = BTreeMap::<CellId, usize>::new();
+
+        for cell in self.get_all().iter().flatten() {
+            *ids.entry(cell.id).or_default() += 1;
+        }
+
+        for (id, count) in ids.iter() {
+            if *count > 1 {
+                violations.push(Violation::DuplicateId {
+                    id: *id,
+                    count: *count,
+                });
+            }
+        }
+
+        for id in 0..self.cells_count() as CellId {
+            if !ids.contains_key(&id) {
+                violations.push(Violation::MissingId { id });
+            }
+        }
+    }
+
+    fn validate_cells(&self, violations: &mut Vec<Violation>) {
+        let max = self.max_mines_per_cell();
+
+        for (x, col) in self.get_all().iter().enumerate() {
+            for (y, cell) in col.iter().enumerate() {
+                let expected = CellPosition {
+                    x: x as i16,
+                    y: y as i16,
+                };
+
+                if cell.position != expected {
+                    violations.push(Violation::WrongPosition {
+                        id: cell.id,
+                        position: cell.position,
+                        expected,
+                    });
+                }
+
+                if cell.ctype.mines() > max {
+                    violations.push(Violation::TooManyMines {
+                        id: cell.id,
+                        mines: cell.ctype.mines(),
+                        max,
+                    });
+                }
+            }
+        }
+
+        // Values of cells which don't exist are never shown
+        for cell in self.get_all().iter().flatten() {
+            let value = match cell.ctype {
+                CellType::Empty(value) if cell.state != CellState::Void => value,
+                _ => continue,
+            };
+
+            let expected = self
+                .neighbours(cell.position)
+                .iter()
+                .map(|neighbour| neighbour.ctype.charge())
+                .sum::<i16>();
+
+            if value as i16 != expected {
+                violations.push(Violation::WrongValue {
+                    id: cell.id,
+                    value,
+                    expected,
+                });
+            }
+        }
+    }
+
+    fn validate_counters(&self, violations: &mut Vec<Violation>) {
+        let cells = self.get_all().iter().flatten();
+        let count_mines = |is_negative: bool| {
+            cells
+                .clone()
+                .filter(|cell| matches!(cell.ctype, CellType::NegativeMine(_)) == is_negative)
+                .map(|cell| cell.ctype.mines() as u16)
+                .sum::<u16>()
+        };
+
+        let (bombs, negative_bombs) = (count_mines(false), count_mines(true));
+        let flags = cells.map(|cell| cell.flags() as u16).sum::<u16>();
+
+        let counters = [
+            (Counter::Bombs, self.bombs(), bombs),
+            (
+                Counter::NegativeBombs,
+                self.negative_bombs(),
+                negative_bombs,
+            ),
+            (
+                Counter::FlagsLeft,
+                self.flags_left,
+                (bombs + negative_bombs).saturating_sub(flags),
+            ),
+        ];
+
+        for (counter, value, expected) in counters.iter() {
+            if value != expected {
+                violations.push(Violation::WrongCounter {
+                    counter: *counter,
+                    value: *value,
+                    expected: *expected,
+                });
+            }
+        }
+    }
+}
+
+#[test]
+fn should_find_nothing_on_generated_battlefields() {
+    use crate::engine::{GameConfig, Tiling};
+
+    let configs = [
+        GameConfig::new(8, 8, 10),
+        GameConfig::new(6, 7, 8).with_tiling(Tiling::Hex),
+        GameConfig::new(6, 6, 6).with_wrap(true),
+        GameConfig::new(6, 6, 12).with_max_mines_per_cell(3),
+        GameConfig::new(6, 6, 5).with_negative_bombs(4),
+        GameConfig::new(4, 4, 6).with_layers(3),
+    ];
+
+    for config in configs.iter() {
+        let battlefield = BattleField::from_config(&config.clone().with_seed(5));
+
+        assert_eq!(battlefield.validate(), vec![]);
+    }
+}
+
+#[test]
+fn should_find_nothing_while_the_game_is_played() {
+    let mut battlefield = BattleField::with_seed(8, 8, 10, 3);
+    let lives = crate::engine::GameConfig::new(8, 8, 10)
+        .with_seed(3)
+        .with_lives(3);
+    let mut with_lives = BattleField::from_config(&lives);
+
+    for id in 0..20 {
+        battlefield.flag(id * 3);
+        battlefield.reveal(id * 3 + 1);
+        with_lives.flag(id * 3);
+        with_lives.reveal(id * 3 + 1);
+
+        assert_eq!(battlefield.validate(), vec![]);
+        assert_eq!(with_lives.validate(), vec![]);
+    }
+}
+
+#[test]
+fn should_find_wrong_values() {
+    // |b, 0|
+    let battlefield = BattleField::with_map(vec![
+        vec![Cell::new(0, CellType::Mine(1), CellPosition { x: 0, y: 0 })],
+        vec![Cell::new(
+            1,
+            CellType::Empty(0),
+            CellPosition { x: 1, y: 0 },
+        )],
+    ]);
+
+    assert_eq!(
+        battlefield.validate(),
+        vec![Violation::WrongValue {
+            id: 1,
+            value: 0,
+            expected: 1
+        }]
+    );
+}
+
+#[test]
+fn should_find_duplicated_and_missing_ids() {
+    let battlefield = BattleField::with_map(vec![
+        vec![Cell::new(
+            0,
+            CellType::Empty(0),
+            CellPosition { x: 0, y: 0 },
+        )],
+        vec![Cell::new(
+            0,
+            CellType::Empty(0),
+            CellPosition { x: 1, y: 0 },
+        )],
+        vec![Cell::new(
+            5,
+            CellType::Empty(0),
+            CellPosition { x: 2, y: 0 },
+        )],
+    ]);
+
+    assert_eq!(
+        battlefield.validate(),
+        vec![
+            Violation::DuplicateId { id: 0, count: 2 },
+            Violation::MissingId { id: 1 },
+            Violation::MissingId { id: 2 },
+        ]
+    );
+}
+
+#[test]
+fn should_find_wrong_positions() {
+    let battlefield = BattleField::with_map(vec![
+        vec![Cell::new(
+            0,
+            CellType::Empty(0),
+            CellPosition { x: 1, y: 0 },
+        )],
+        vec![Cell::new(
+            1,
+            CellType::Empty(0),
+            CellPosition { x: 0, y: 0 },
+        )],
+    ]);
+
+    assert_eq!(
+        battlefield.validate(),
+        vec![
+            Violation::WrongPosition {
+                id: 0,
+                position: CellPosition { x: 1, y: 0 },
+                expected: CellPosition { x: 0, y: 0 },
+            },
+            Violation::WrongPosition {
+                id: 1,
+                position: CellPosition { x: 0, y: 0 },
+                expected: CellPosition { x: 1, y: 0 },
+            },
+        ]
+    );
+}
+
+#[test]
+fn should_find_wrong_counters() {
+    // |b, 1|
+    let mut battlefield = BattleField::with_map(vec![
+        vec![Cell::new(0, CellType::Mine(1), CellPosition { x: 0, y: 0 })],
+        vec![Cell::new(
+            1,
+            CellType::Empty(1),
+            CellPosition { x: 1, y: 0 },
+        )],
+    ]);
+    battlefield.flags_left = 4;
+    battlefield.get_mut(1).state = CellState::Flagged(1);
+
+    assert_eq!(
+        battlefield.validate(),
+        vec![Violation::WrongCounter {
+            counter: Counter::FlagsLeft,
+            value: 4,
+            expected: 0,
+        }]
+    );
+}
+
+#[test]
+fn should_describe_violations() {
+    let violation = Violation::WrongValue {
+        id: 3,
+        value: 2,
+        expected: 1,
+    };
+
+    assert_eq!(
+        violation.to_string(),
+        "cell 3 has value 2 but 1 mines are around it"
+    );
+}
+
+#[test]
+fn should_find_nothing_when_the_game_is_lost() {
+    let mut battlefield = BattleField::with_seed(6, 6, 8, 4);
+    let mine = battlefield
+        .get_all()
+        .iter()
+        .flatten()
+        .find(|cell| cell.ctype.is_mine())
+        .map(|cell| cell.id)
+        .unwrap();
+
+    battlefield.flag(0);
+    battlefield.flag(1);
+    battlefield.reveal(mine);
+
+    assert_eq!(battlefield.validate(), vec![]);
+}
@@ -1,8 +1,12 @@
 pub mod engine;
+
+#[cfg(feature = "wasm")]
 mod wasm;
 
-pub use wasm::MineSweeperEngine;
+#[cfg(feature = "wasm")]
+pub use wasm::{EditorEngine, GameConfig, MineSweeperEngine, WasmTopology};
 
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
 // When the `wee_alloc` feature is enabled, this uses `wee_alloc` as the global
@@ -14,6 +18,7 @@ use wasm_bindgen::prelude::*;
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
 // This is like the `main` function, except for JavaScript.
+#[cfg(feature = "wasm")]
 #[wasm_bindgen(start)]
 pub fn main_js() -> Result<(), JsValue> {
     // This provides better error messages in debug mode.
@@ -0,0 +1,134 @@
+use wasm_bindgen::prelude::*;
+
+use crate::engine::{BoardEditor, Cell, CellCoordinates, CellId, CellPosition};
+use crate::wasm::mine_sweeper::{engine_config, GameConfig, MineSweeperEngine};
+use crate::wasm::wasm_types::*;
+
+/// Editor of puzzle boards which keeps values of empty cells
+///  in line with mines around them
+///
+/// Unlike `MineSweeperEngine` it shows types of all cells
+#[wasm_bindgen]
+pub struct EditorEngine {
+    editor: BoardEditor,
+}
+
+#[wasm_bindgen]
+impl EditorEngine {
+    /// Creates the editor of the battlefield without mines
+    ///  which has the shape described by `config`
+    ///  or returns the `RangeError` if it can't be built
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: GameConfig) -> Result<EditorEngine, JsValue> {
+        Ok(Self {
+            editor: BoardEditor::new(&engine_config(config)?),
+        })
+    }
+
+    /// Puts the mine into the empty cell by providing row, column
+    ///  and optional layer or removes it from the mined one
+    ///  and returns the actual cell
+    #[wasm_bindgen(js_name = toggleMine)]
+    pub fn toggle_mine(
+        &mut self,
+        row: i16,
+        col: i16,
+        layer: Option<i16>,
+    ) -> Result<JsValue, JsValue> {
+        let coordinates = CellCoordinates {
+            x: col,
+            y: row,
+            z: layer.unwrap_or(0),
+        };
+        let cell = self
+            .editor
+            .battlefield()
+            .existing_cell(coordinates)
+            .map(|cell| cell.position)
+            .and_then(|position| self.editor.toggle_mine(position))
+            .map_err(|error| JsValue::from(js_sys::RangeError::new(&error)))?;
+
+        Ok(convert_cell_into_wasm(cell))
+    }
+
+    /// Replaces all mines by single mines in cells with provided ids,
+    ///  so the board saved by `getMines` may be built again
+    #[wasm_bindgen(js_name = setMines)]
+    pub fn set_mines(&mut self, ids: Vec<CellId>) -> Result<(), JsValue> {
+        self.editor
+            .set_mines(&ids)
+            .map_err(|error| JsValue::from(js_sys::RangeError::new(&error)))
+    }
+
+    /// Removes all mines from the battlefield
+    pub fn clear(&mut self) {
+        self.editor.clear();
+    }
+
+    /// Replaces mines of the rectangle between provided rows and columns
+    ///  including both corners by `bombs` mines in random cells
+    ///
+    /// If `seed` is provided the same seed always picks the same cells
+    #[wasm_bindgen(js_name = randomizeRegion)]
+    pub fn randomize_region(
+        &mut self,
+        from_row: i16,
+        from_col: i16,
+        to_row: i16,
+        to_col: i16,
+        bombs: u16,
+        seed: Option<u32>,
+    ) -> Result<(), JsValue> {
+        let from = CellPosition {
+            x: from_col,
+            y: from_row,
+        };
+        let to = CellPosition {
+            x: to_col,
+            y: to_row,
+        };
+
+        self.editor
+            .randomize_region(from, to, bombs, seed.map(u64::from))
+            .map_err(|error| JsValue::from(js_sys::Error::new(&error)))
+    }
+
+    /// Returns ids of all cells which contain mines
+    #[wasm_bindgen(js_name = getMines)]
+    pub fn mines(&self) -> Vec<CellId> {
+        self.editor.mines()
+    }
+
+    /// Returns how many mines the battlefield has
+    #[wasm_bindgen(js_name = getBombs)]
+    pub fn bombs(&self) -> u16 {
+        self.editor.battlefield().bombs()
+    }
+
+    /// Returns the whole map with types of all cells
+    #[wasm_bindgen(js_name = getField)]
+    pub fn get_field(&self) -> js_sys::Array {
+        self.editor
+            .battlefield()
+            .get_all()
+            .iter()
+            .map(|col| {
+                col.iter()
+                    .map(convert_cell_into_wasm)
+                    .collect::<js_sys::Array>()
+            })
+            .collect()
+    }
+
+    /// Creates the game on the copy of the edited battlefield
+    ///  with rules of the config, the editor may be used
+    ///  to change the board further
+    pub fn play(&self) -> MineSweeperEngine {
+        MineSweeperEngine::from_game(self.editor.play())
+    }
+}
+
+/// Converts the cell into `WasmCell` with its type
+fn convert_cell_into_wasm(cell: &Cell) -> JsValue {
+    JsValue::from(WasmCell::from(&cell.player_view(true)))
+}
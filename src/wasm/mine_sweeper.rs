@@ -1,6 +1,7 @@
 use wasm_bindgen::prelude::*;
 
-use crate::engine::{BattleField, Cell, CellId, CellState, CellType};
+use crate::engine::encoding::EncodedBoard;
+use crate::engine::{self, Cell, CellCoordinates, CellId, Game, SubscriptionId};
 use crate::wasm::wasm_types::*;
 
 #[wasm_bindgen]
@@ -8,6 +9,9 @@ use crate::wasm::wasm_types::*;
 pub struct GameState {
     pub status: GameStatus,
     pub flags: u16,
+
+    /// How many mines the player may still hit
+    pub lives: u8,
 }
 
 #[wasm_bindgen]
@@ -23,22 +27,195 @@ pub enum GameStatus {
     Won,
 }
 
+impl From<engine::GameState> for GameState {
+    fn from(game_state: engine::GameState) -> Self {
+        Self {
+            status: match game_state.status {
+                engine::GameStatus::Played => GameStatus::Played,
+                engine::GameStatus::Lose => GameStatus::Lose,
+                engine::GameStatus::Won => GameStatus::Won,
+            },
+            flags: game_state.flags,
+            lives: game_state.lives,
+        }
+    }
+}
+
+/// Settings of the new game which is started by `newGame`
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct GameConfig {
+    pub rows: u16,
+    pub cols: u16,
+    pub bombs: u16,
+
+    /// How many layers of `rows x cols` the map has, `1` by default
+    pub layers: u16,
+
+    /// The shape of cells, `Square` by default,
+    ///  it's the tiling of the engine config
+    pub topology: WasmTopology,
+
+    /// If `true` opposite edges of the map are neighbours
+    pub wrap: bool,
+
+    /// How many negative mines are placed in addition to `bombs`
+    #[wasm_bindgen(js_name = negativeBombs)]
+    pub negative_bombs: u16,
+
+    /// How many mines a single cell may contain, `1` by default,
+    ///  it's lowered if values of cells wouldn't fit `i8`
+    #[wasm_bindgen(js_name = maxMinesPerCell)]
+    pub max_mines_per_cell: u8,
+
+    /// How many mines the player may hit before the game is lost,
+    ///  `1` by default
+    pub lives: u8,
+
+    /// When the game is won, `RevealAll` by default
+    #[wasm_bindgen(js_name = winRule)]
+    pub win_rule: WasmWinRule,
+
+    /// What happens to mines when the player guesses, `Normal` by default
+    #[wasm_bindgen(js_name = guessMode)]
+    pub guess_mode: WasmGuessMode,
+
+    /// Cells which are counted by values of empty cells,
+    ///  it's set by `setNeighbourhood`
+    neighbourhood: engine::Neighbourhood,
+
+    /// The shape of the map, it's set by `setMask` or `setBitmapMask`
+    mask: Option<engine::Mask>,
+}
+
+#[wasm_bindgen]
+impl GameConfig {
+    #[wasm_bindgen(constructor)]
+    pub fn new(rows: u16, cols: u16, bombs: u16) -> Self {
+        Self {
+            rows,
+            cols,
+            bombs,
+            layers: 1,
+            topology: WasmTopology::Square,
+            wrap: false,
+            negative_bombs: 0,
+            max_mines_per_cell: 1,
+            lives: 1,
+            win_rule: WasmWinRule::RevealAll,
+            guess_mode: WasmGuessMode::Normal,
+            neighbourhood: engine::Neighbourhood::Moore,
+            mask: None,
+        }
+    }
+
+    /// Sets cells which are counted by values of empty cells:
+    ///  `moore` (default), `von-neumann`, `knight`, `radius-2`
+    ///  or custom offsets as `custom:x,y;x,y`
+    #[wasm_bindgen(js_name = setNeighbourhood)]
+    pub fn set_neighbourhood(&mut self, neighbourhood: &str) -> Result<(), JsValue> {
+        self.neighbourhood = neighbourhood
+            .parse()
+            .map_err(|error: String| JsValue::from(js_sys::Error::new(&error)))?;
+
+        Ok(())
+    }
+
+    /// Sets the shape of the map from the ASCII pattern where every
+    ///  line is a row, `.` and spaces are cells which don't exist
+    ///
+    /// Rows and columns are replaced by the size of the pattern
+    #[wasm_bindgen(js_name = setMask)]
+    pub fn set_mask(&mut self, pattern: &str) -> Result<(), JsValue> {
+        let mask = engine::Mask::from_ascii(pattern)
+            .map_err(|error| JsValue::from(js_sys::Error::new(&error)))?;
+
+        self.apply_mask(mask);
+
+        Ok(())
+    }
+
+    /// Sets the shape of the map from the bitmap of `width x height`
+    ///  pixels stored row by row, where non-zero pixels are cells
+    ///
+    /// Rows and columns are replaced by the size of the bitmap
+    #[wasm_bindgen(js_name = setBitmapMask)]
+    pub fn set_bitmap_mask(
+        &mut self,
+        width: u16,
+        height: u16,
+        pixels: &[u8],
+    ) -> Result<(), JsValue> {
+        let mask = engine::Mask::from_bitmap(width as usize, height as usize, pixels)
+            .map_err(|error| JsValue::from(js_sys::Error::new(&error)))?;
+
+        self.apply_mask(mask);
+
+        Ok(())
+    }
+}
+
+impl GameConfig {
+    fn apply_mask(&mut self, mask: engine::Mask) {
+        self.rows = mask.rows() as u16;
+        self.cols = mask.cols() as u16;
+        self.mask = Some(mask);
+    }
+}
+
+impl MineSweeperEngine {
+    /// Creates the engine of already existing game
+    pub(crate) fn from_game(game: Game) -> Self {
+        Self {
+            board: EncodedBoard::new(&game),
+            game,
+            on_change: None,
+        }
+    }
+}
+
+impl From<GameConfig> for engine::GameConfig {
+    fn from(config: GameConfig) -> Self {
+        let engine_config = Self::new(config.rows as usize, config.cols as usize, config.bombs)
+            .with_layers(config.layers as usize)
+            .with_tiling(config.topology.into())
+            .with_wrap(config.wrap)
+            .with_neighbourhood(config.neighbourhood)
+            .with_negative_bombs(config.negative_bombs)
+            .with_max_mines_per_cell(config.max_mines_per_cell)
+            .with_lives(config.lives)
+            .with_win_rule(config.win_rule.into())
+            .with_guess_mode(config.guess_mode.into());
+
+        match config.mask {
+            Some(mask) => engine_config.with_mask(mask),
+            None => engine_config,
+        }
+    }
+}
+
+/// Converts the config into the engine one or returns the `RangeError`
+///  if the battlefield can't be built by it
+pub(crate) fn engine_config(config: GameConfig) -> Result<engine::GameConfig, JsValue> {
+    let config = engine::GameConfig::from(config);
+    config
+        .validate()
+        .map_err(|error| JsValue::from(js_sys::RangeError::new(&error)))?;
+
+    Ok(config)
+}
+
 #[wasm_bindgen]
 /// The main Minesweeper engine which contain
 ///  - rows
 ///  - cols
 pub struct MineSweeperEngine {
-    /// The main battlefield of mine sweeper
-    battlefield: BattleField,
-
-    /// Returns the game state of the game
-    game_state: GameState,
+    /// The game which contains the battlefield and game state
+    game: Game,
 
-    /// How many cells should we reveal to win the game
-    elements_to_win_the_game: i16,
-
-    /// How many cells already revealed
-    revealed_elements: i16,
+    /// The battlefield packed into typed arrays
+    ///  which the client may read without copying
+    board: EncodedBoard,
 
     /// A public subsciption that we have to trigger
     ///  if the client want to know if game state
@@ -49,70 +226,173 @@ pub struct MineSweeperEngine {
 #[wasm_bindgen]
 impl MineSweeperEngine {
     /// Creates the engine and matrix battlefield by providing
-    ///  rows and columns
-    pub fn create(rows: u16, cols: u16, bombs: u16) -> Self {
-        let battlefield = BattleField::new(rows as usize, cols as usize, bombs);
-        let flgs = battlefield.flags_left;
+    ///  rows and columns or returns the `RangeError`
+    ///  if the battlefield is too large
+    pub fn create(rows: u16, cols: u16, bombs: u16) -> Result<MineSweeperEngine, JsValue> {
+        Self::create_with_config(GameConfig::new(rows, cols, bombs))
+    }
 
-        Self {
-            battlefield,
-            game_state: GameState {
-                status: GameStatus::Played,
-                flags: flgs,
-            },
-            elements_to_win_the_game: (rows * cols - bombs) as i16,
-            revealed_elements: 0,
-            on_change: None,
-        }
+    /// Creates the engine with the battlefield described by `config`
+    ///  or returns the `RangeError` if the battlefield can't be built
+    #[wasm_bindgen(js_name = createWithConfig)]
+    pub fn create_with_config(config: GameConfig) -> Result<MineSweeperEngine, JsValue> {
+        Ok(Self::from_game(Game::from_config(&engine_config(config)?)))
+    }
+
+    /// Starts the same map over with all cells hidden
+    ///
+    /// Listeners are kept, the board view is re-encoded
+    ///  and the diff view contains every changed cell
+    pub fn restart(&mut self) {
+        self.game.restart();
+        self.on_game_reset();
+    }
+
+    /// Starts a new game with a new random battlefield
+    ///  which may have another size
+    ///
+    /// Listeners are kept, the board view is re-encoded
+    ///  and the diff view contains every changed cell
+    ///
+    /// If the battlefield can't be built by `config` the `RangeError`
+    ///  is returned and the current game goes on
+    #[wasm_bindgen(js_name = newGame)]
+    pub fn new_game(&mut self, config: GameConfig) -> Result<(), JsValue> {
+        self.game.new_game(&engine_config(config)?);
+        self.on_game_reset();
+
+        Ok(())
+    }
+
+    /// Starts a new game of the same size with the battlefield
+    ///  generated from provided `seed`
+    ///
+    /// Listeners are kept, the board view is re-encoded
+    ///  and the diff view contains every changed cell
+    pub fn rematch(&mut self, seed: u32) {
+        self.game.rematch(seed as u64);
+        self.on_game_reset();
     }
 
     /// Reveals the cell by providing id
     pub fn reveal(&mut self, cell_id: CellId) -> js_sys::Array {
-        let reveal = self.battlefield.reveal(cell_id);
+        let reveal = self.game.reveal(cell_id);
 
-        // Add into `revealed_elements` all new revealed cells count
-        self.revealed_elements += reveal.cells.len() as i16;
+        // Mines flagged on the win are changed too
+        let changed_cells = reveal.cells.iter().chain(reveal.flagged.iter());
+        let changed = changed_cells
+            .clone()
+            .map(|cell| cell.id)
+            .collect::<Vec<_>>();
+        self.board.update(&self.game, &changed);
 
         // Returns a vector of changed cells
-        let cells = reveal
-            .cells
-            .into_iter()
-            .map(|ref cell| self.convert_cell_into_wasm(cell))
+        let cells = changed_cells
+            .map(|cell| self.convert_cell_into_wasm(cell))
             .collect();
 
-        // Updates `game_is_over` flag to set the actual game state
-        if reveal.game_is_over {
-            self.game_state.status = GameStatus::Lose;
-        } else if self.elements_to_win_the_game - self.revealed_elements == 0 {
-            self.game_state.status = GameStatus::Won;
-        }
-
-        self.game_state.flags = self.battlefield.flags_left;
-        self.on_game_changed(&self.game_state);
+        self.on_game_changed(&self.game_state());
 
         cells
     }
 
     pub fn flag(&mut self, cell_id: CellId) -> JsValue {
-        let cell = self.battlefield.flag(cell_id);
-        let cp_cell = *cell;
+        let cell = self.game.flag(cell_id);
+        self.board.update(&self.game, &[cell.id]);
+
+        self.on_game_changed(&self.game_state());
+
+        self.convert_cell_into_wasm(&cell)
+    }
+
+    /// Reveals all hidden neighbours of the revealed cell by providing id
+    ///  if the cell has as many flags around as its value
+    pub fn chord(&mut self, cell_id: CellId) -> js_sys::Array {
+        let reveal = self.game.chord(cell_id);
+
+        // Mines flagged on the win are changed too
+        let changed_cells = reveal.cells.iter().chain(reveal.flagged.iter());
+        let changed = changed_cells
+            .clone()
+            .map(|cell| cell.id)
+            .collect::<Vec<_>>();
+        self.board.update(&self.game, &changed);
+
+        // Returns a vector of changed cells
+        let cells = changed_cells
+            .map(|cell| self.convert_cell_into_wasm(cell))
+            .collect();
+
+        self.on_game_changed(&self.game_state());
+
+        cells
+    }
+
+    /// Reveals the cell by providing row, column and the layer
+    ///  which is `0` if it isn't provided
+    #[wasm_bindgen(js_name = revealAt)]
+    pub fn reveal_at(
+        &mut self,
+        row: i16,
+        col: i16,
+        layer: Option<i16>,
+    ) -> Result<js_sys::Array, JsValue> {
+        let cell_id = self.cell_id_at(row, col, layer)?;
+
+        Ok(self.reveal(cell_id))
+    }
 
-        self.game_state.flags = self.battlefield.flags_left;
-        self.on_game_changed(&self.game_state);
+    /// Flags or unflags the cell by providing row, column and the layer
+    ///  which is `0` if it isn't provided
+    #[wasm_bindgen(js_name = flagAt)]
+    pub fn flag_at(&mut self, row: i16, col: i16, layer: Option<i16>) -> Result<JsValue, JsValue> {
+        let cell_id = self.cell_id_at(row, col, layer)?;
 
-        self.convert_cell_into_wasm(&cp_cell)
+        Ok(self.flag(cell_id))
+    }
+
+    /// Chords the cell by providing row, column and the layer
+    ///  which is `0` if it isn't provided
+    #[wasm_bindgen(js_name = chordAt)]
+    pub fn chord_at(
+        &mut self,
+        row: i16,
+        col: i16,
+        layer: Option<i16>,
+    ) -> Result<js_sys::Array, JsValue> {
+        let cell_id = self.cell_id_at(row, col, layer)?;
+
+        Ok(self.chord(cell_id))
+    }
+
+    /// Returns the cell by providing row, column and the layer
+    ///  which is `0` if it isn't provided
+    #[wasm_bindgen(js_name = cellAt)]
+    pub fn cell_at(&self, row: i16, col: i16, layer: Option<i16>) -> Result<JsValue, JsValue> {
+        let cell_id = self.cell_id_at(row, col, layer)?;
+        let cell = *self.game.battlefield().get(cell_id);
+
+        Ok(self.convert_cell_into_wasm(&cell))
     }
 
     /// Returns a game state of the game
     #[wasm_bindgen(js_name = getGameState)]
     pub fn game_state(&self) -> GameState {
-        self.game_state
+        GameState::from(self.game.state())
+    }
+
+    /// Returns which mine exploded and how good the flags were
+    ///  if the game has been lost
+    #[wasm_bindgen(js_name = getLossReport)]
+    pub fn loss_report(&self) -> Option<WasmLossReport> {
+        self.game.loss_report().map(WasmLossReport::from)
     }
 
     /// Returns map to the client
     #[wasm_bindgen(js_name = getField)]
     pub fn get_field(&self) -> js_sys::Array {
-        self.battlefield
+        self.game
+            .battlefield()
             .get_all()
             .iter()
             .map(|cell_vec| {
@@ -125,6 +405,99 @@ impl MineSweeperEngine {
             .collect()
     }
 
+    /// Returns cells of the layer `z` of the 3D battlefield
+    ///  where the first array is columns and the second one is rows
+    #[wasm_bindgen(js_name = getLayer)]
+    pub fn get_layer(&self, z: u16) -> Result<js_sys::Array, JsValue> {
+        let battlefield = self.game.battlefield();
+
+        if z as usize >= battlefield.layers() {
+            let message = format!(
+                "Layer {} is outside of the battlefield with {} layers",
+                z,
+                battlefield.layers()
+            );
+
+            return Err(JsValue::from(js_sys::RangeError::new(&message)));
+        }
+
+        Ok(battlefield
+            .layer(z as usize)
+            .iter()
+            .map(|col| {
+                col.iter()
+                    .map(|cell| self.convert_cell_into_wasm(cell))
+                    .collect::<js_sys::Array>()
+            })
+            .collect())
+    }
+
+    /// Returns how many layers the battlefield has
+    #[wasm_bindgen(js_name = getLayers)]
+    pub fn layers(&self) -> usize {
+        self.game.battlefield().layers()
+    }
+
+    /// Returns how many rows every layer of the battlefield has
+    #[wasm_bindgen(js_name = getRows)]
+    pub fn rows(&self) -> usize {
+        self.game.battlefield().rows()
+    }
+
+    /// Returns how many columns the battlefield has
+    #[wasm_bindgen(js_name = getCols)]
+    pub fn cols(&self) -> usize {
+        self.game.battlefield().cols()
+    }
+
+    /// Returns the shape of cells which the renderer has to draw
+    #[wasm_bindgen(js_name = getTopology)]
+    pub fn topology(&self) -> WasmTopology {
+        self.game.battlefield().topology().tiling().into()
+    }
+
+    /// Returns cells which are counted by values of empty cells
+    ///  in the same format as `GameConfig.setNeighbourhood` accepts
+    #[wasm_bindgen(js_name = getNeighbourhood)]
+    pub fn neighbourhood(&self) -> String {
+        self.game
+            .battlefield()
+            .topology()
+            .neighbourhood()
+            .to_string()
+    }
+
+    /// Returns `true` if opposite edges of the map are neighbours
+    #[wasm_bindgen(js_name = isWrapped)]
+    pub fn is_wrapped(&self) -> bool {
+        self.game.battlefield().topology().is_wrapped()
+    }
+
+    /// Returns the whole battlefield as a `Uint16Array` where
+    ///  index is the cell id (`col * rows + row`) and every value
+    ///  is the packed cell (see `engine::encoding` for the layout)
+    ///
+    /// The array is a view into the Web Assembly memory,
+    ///  it's updated in place by every action and must not be kept
+    ///  after the next call into the engine, because allocations
+    ///  may grow the memory and detach the view
+    #[wasm_bindgen(js_name = getBoardView)]
+    pub fn board_view(&self) -> js_sys::Uint16Array {
+        // SAFETY: the view doesn't outlive the buffer until the
+        //  client calls the engine again (see the note above)
+        unsafe { js_sys::Uint16Array::view(self.board.cells()) }
+    }
+
+    /// Returns ids of cells which were changed by the last action
+    ///  as a `Uint16Array` view into the Web Assembly memory
+    ///
+    /// The same rules as for `getBoardView` are applied
+    #[wasm_bindgen(js_name = getDiffView)]
+    pub fn diff_view(&self) -> js_sys::Uint16Array {
+        // SAFETY: the same as for `board_view`
+        unsafe { js_sys::Uint16Array::view(self.board.diff()) }
+    }
+
     /// Method that can be subscribed to in the Public API
     ///  that signals that the state of the application
     ///  has been changed
@@ -135,6 +508,29 @@ impl MineSweeperEngine {
         self.on_change = Some(callback);
     }
 
+    /// Adds the listener which receives every `WasmGameEvent`
+    ///  of the game and returns the identificator which
+    ///  may be used to remove the listener
+    ///
+    /// Any number of listeners may be added
+    #[wasm_bindgen(js_name = addEventListener)]
+    pub fn add_event_listener(&mut self, callback: js_sys::Function) -> SubscriptionId {
+        self.game.subscribe(move |event| {
+            let this = JsValue::null();
+            let wasm_event = JsValue::from(WasmGameEvent::from(event));
+
+            let _ = callback.call1(&this, &wasm_event);
+        })
+    }
+
+    /// Removes the listener which was added by `addEventListener`
+    ///
+    /// Returns `false` if there was no such listener
+    #[wasm_bindgen(js_name = removeEventListener)]
+    pub fn remove_event_listener(&mut self, id: SubscriptionId) -> bool {
+        self.game.unsubscribe(id)
+    }
+
     /// Fires when game was changed
     /// Internal method which called public `on_change` event
     fn on_game_changed(&self, game_state: &GameState) {
@@ -146,27 +542,34 @@ impl MineSweeperEngine {
         }
     }
 
-    /// Converts Battlefield Cell into WasmCell structure
-    fn convert_cell_into_wasm(&self, cell: &Cell) -> JsValue {
-        let wasm_cell = WasmCell {
-            id: cell.id,
-            status: match cell.state {
-                CellState::Hidden => WasmCellState::Hidden,
-                CellState::Revealed => WasmCellState::Revealed,
-                CellState::Flagged => WasmCellState::Flagged,
-            },
-            ctype: WasmCType {
-                name: match cell.ctype {
-                    CellType::Mine => WasmCTypeName::Mine,
-                    CellType::Empty(_) => WasmCTypeName::Empty,
-                },
-                value: match cell.ctype {
-                    CellType::Mine => 0,
-                    CellType::Empty(value) => value,
-                },
-            },
+    fn on_game_reset(&mut self) {
+        self.board.reset(&self.game);
+        self.on_game_changed(&self.game_state());
+    }
+
+    /// Returns id of the cell by providing row, column and the layer
+    ///  or the JS error if the cell is outside of the battlefield
+    ///  or doesn't exist
+    fn cell_id_at(&self, row: i16, col: i16, layer: Option<i16>) -> Result<CellId, JsValue> {
+        let coordinates = CellCoordinates {
+            x: col,
+            y: row,
+            z: layer.unwrap_or(0),
         };
 
-        JsValue::from(wasm_cell)
+        self.game
+            .battlefield()
+            .existing_cell(coordinates)
+            .map(|cell| cell.id)
+            .map_err(|error| JsValue::from(js_sys::RangeError::new(&error)))
+    }
+
+    /// Converts Battlefield Cell into WasmCell structure
+    ///
+    /// The type of the cell is sent only if the player
+    ///  is allowed to see it, so mines can't be found
+    ///  by looking into the data in devtools
+    fn convert_cell_into_wasm(&self, cell: &Cell) -> JsValue {
+        JsValue::from(WasmCell::from(&self.game.player_cell(cell)))
     }
 }
@@ -1,4 +1,7 @@
+mod editor;
 mod mine_sweeper;
 mod wasm_types;
 
-pub use mine_sweeper::MineSweeperEngine;
+pub use editor::EditorEngine;
+pub use mine_sweeper::{GameConfig, MineSweeperEngine};
+pub use wasm_types::WasmTopology;
@@ -1,19 +1,31 @@
 use wasm_bindgen::prelude::*;
 
-use crate::engine::CellId;
+use crate::engine::{
+    Cell, CellId, CellState, CellType, GameEvent, GuessMode, LossReport, PlayerCell, Tiling,
+    WinRule,
+};
 
 #[wasm_bindgen]
 #[derive(Copy, Clone)]
 pub enum WasmCTypeName {
     Mine,
+
+    /// The mine which subtracts one from values of cells around it
+    NegativeMine,
     Empty,
+
+    /// The player isn't allowed to know the type of the cell yet
+    Unknown,
 }
 
 #[wasm_bindgen]
 #[derive(Copy, Clone)]
 pub struct WasmCType {
     pub name: WasmCTypeName,
-    pub value: u8,
+
+    /// Value of the empty cell which may be negative
+    ///  or count of mines of the mine
+    pub value: i8,
 }
 
 /// The ordinary `Cell` but only for Web Assembly
@@ -31,10 +43,28 @@ pub struct WasmCType {
 /// - [Why we couldn't use `String` as public in structures for Wasm](https://github.com/rustwasm/wasm-bindgen/issues/1775#issuecomment-533761425)
 /// - [Getters and Setters](https://rustwasm.github.io/docs/wasm-bindgen/reference/attributes/on-rust-exports/getter-and-setter.html)
 #[wasm_bindgen]
+#[derive(Copy, Clone)]
 pub struct WasmCell {
     pub id: CellId,
     pub ctype: WasmCType,
     pub status: WasmCellState,
+
+    /// How many flags the player has put on the cell
+    pub flags: u8,
+    pub position: WasmCellPosition,
+}
+
+/// Position of the cell on the battlefield
+///  - `x` - represents column
+///  - `y` - represents row
+///
+/// Layers of 3D battlefields are stacked along rows,
+///  so `y` of the cell on the layer `z` is `z * rows + row`
+#[wasm_bindgen]
+#[derive(Copy, Clone)]
+pub struct WasmCellPosition {
+    pub x: i16,
+    pub y: i16,
 }
 
 #[wasm_bindgen]
@@ -43,4 +73,226 @@ pub enum WasmCellState {
     Hidden,
     Revealed,
     Flagged,
+
+    /// The cell is flagged as a negative mine
+    NegativeFlagged,
+
+    /// The mine has been hit by the player
+    Exploded,
+
+    /// The cell has been flagged but it isn't such a mine,
+    ///  it's shown when the game is lost
+    WrongFlag,
+
+    /// The cell doesn't exist and mustn't be drawn
+    Void,
+}
+
+impl From<&PlayerCell> for WasmCell {
+    fn from(cell: &PlayerCell) -> Self {
+        Self {
+            id: cell.id,
+            status: match cell.state {
+                CellState::Hidden => WasmCellState::Hidden,
+                CellState::Revealed => WasmCellState::Revealed,
+                CellState::Flagged(_) => WasmCellState::Flagged,
+                CellState::NegativeFlagged(_) => WasmCellState::NegativeFlagged,
+                CellState::Exploded => WasmCellState::Exploded,
+                CellState::WrongFlag => WasmCellState::WrongFlag,
+                CellState::Void => WasmCellState::Void,
+            },
+            flags: cell.state.flags(),
+            ctype: WasmCType {
+                name: match cell.ctype {
+                    Some(CellType::Mine(_)) => WasmCTypeName::Mine,
+                    Some(CellType::NegativeMine(_)) => WasmCTypeName::NegativeMine,
+                    Some(CellType::Empty(_)) => WasmCTypeName::Empty,
+                    None => WasmCTypeName::Unknown,
+                },
+                value: match cell.ctype {
+                    Some(CellType::Empty(value)) => value,
+                    Some(ctype) => ctype.mines() as i8,
+                    None => 0,
+                },
+            },
+            position: WasmCellPosition {
+                x: cell.position.x,
+                y: cell.position.y,
+            },
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WasmGameEventKind {
+    TimerStarted,
+    CellRevealed,
+    CellFlagged,
+    CellUnflagged,
+    MineDetonated,
+    GameWon,
+    GameLost,
+    GameReset,
+}
+
+/// The ordinary `GameEvent` but only for Web Assembly
+///
+/// Events about cells carry the cell, other events don't
+#[wasm_bindgen]
+pub struct WasmGameEvent {
+    kind: WasmGameEventKind,
+    cell: Option<WasmCell>,
+}
+
+#[wasm_bindgen]
+impl WasmGameEvent {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> WasmGameEventKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn cell(&self) -> Option<WasmCell> {
+        self.cell
+    }
+}
+
+impl From<&GameEvent> for WasmGameEvent {
+    fn from(event: &GameEvent) -> Self {
+        let (kind, cell) = match event {
+            GameEvent::TimerStarted => (WasmGameEventKind::TimerStarted, None),
+            GameEvent::CellRevealed(cell) => (WasmGameEventKind::CellRevealed, Some(cell)),
+            GameEvent::CellFlagged(cell) => (WasmGameEventKind::CellFlagged, Some(cell)),
+            GameEvent::CellUnflagged(cell) => (WasmGameEventKind::CellUnflagged, Some(cell)),
+            GameEvent::MineDetonated(cell) => (WasmGameEventKind::MineDetonated, Some(cell)),
+            GameEvent::GameWon => (WasmGameEventKind::GameWon, None),
+            GameEvent::GameLost => (WasmGameEventKind::GameLost, None),
+            GameEvent::GameReset => (WasmGameEventKind::GameReset, None),
+        };
+
+        // Events about cells come while the game is going
+        //  so only revealed cells may expose their types
+        Self {
+            kind,
+            cell: cell.map(|cell| WasmCell::from(&cell.player_view(false))),
+        }
+    }
+}
+
+/// The ordinary `LossReport` but only for Web Assembly
+///
+/// Cells are provided by ids, their states are known from the board
+#[wasm_bindgen]
+pub struct WasmLossReport {
+    detonated: CellId,
+    wrong_flags: Vec<CellId>,
+    correct_flags: Vec<CellId>,
+    unflagged_mines: Vec<CellId>,
+}
+
+#[wasm_bindgen]
+impl WasmLossReport {
+    /// The mine which has taken the last life
+    #[wasm_bindgen(getter)]
+    pub fn detonated(&self) -> CellId {
+        self.detonated
+    }
+
+    /// Flagged cells which don't contain such mines
+    #[wasm_bindgen(getter, js_name = wrongFlags)]
+    pub fn wrong_flags(&self) -> Vec<CellId> {
+        self.wrong_flags.clone()
+    }
+
+    /// Flagged cells which contain such mines
+    #[wasm_bindgen(getter, js_name = correctFlags)]
+    pub fn correct_flags(&self) -> Vec<CellId> {
+        self.correct_flags.clone()
+    }
+
+    /// Mines which were neither flagged nor hit
+    #[wasm_bindgen(getter, js_name = unflaggedMines)]
+    pub fn unflagged_mines(&self) -> Vec<CellId> {
+        self.unflagged_mines.clone()
+    }
+}
+
+impl From<&LossReport> for WasmLossReport {
+    fn from(report: &LossReport) -> Self {
+        let ids = |cells: &[Cell]| cells.iter().map(|cell| cell.id).collect();
+
+        Self {
+            detonated: report.detonated.id,
+            wrong_flags: ids(&report.wrong_flags),
+            correct_flags: ids(&report.correct_flags),
+            unflagged_mines: ids(&report.unflagged_mines),
+        }
+    }
+}
+
+/// The ordinary `Tiling` but only for Web Assembly, the name
+///  is kept from the time it was called the topology of the map
+///
+/// `Hex` positions are axial coordinates, so the renderer has
+///  to shift every next row by half of the tile
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WasmTopology {
+    Square,
+    Hex,
+}
+
+impl From<Tiling> for WasmTopology {
+    fn from(tiling: Tiling) -> Self {
+        match tiling {
+            Tiling::Square => WasmTopology::Square,
+            Tiling::Hex => WasmTopology::Hex,
+        }
+    }
+}
+
+impl From<WasmTopology> for Tiling {
+    fn from(tiling: WasmTopology) -> Self {
+        match tiling {
+            WasmTopology::Square => Tiling::Square,
+            WasmTopology::Hex => Tiling::Hex,
+        }
+    }
+}
+
+/// The ordinary `GuessMode` but only for Web Assembly
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WasmGuessMode {
+    Normal,
+    Kind,
+    Cruel,
+}
+
+impl From<WasmGuessMode> for GuessMode {
+    fn from(guess_mode: WasmGuessMode) -> Self {
+        match guess_mode {
+            WasmGuessMode::Normal => GuessMode::Normal,
+            WasmGuessMode::Kind => GuessMode::Kind,
+            WasmGuessMode::Cruel => GuessMode::Cruel,
+        }
+    }
+}
+
+/// The ordinary `WinRule` but only for Web Assembly
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WasmWinRule {
+    RevealAll,
+    RevealOrFlagAll,
+}
+
+impl From<WasmWinRule> for WinRule {
+    fn from(win_rule: WasmWinRule) -> Self {
+        match win_rule {
+            WasmWinRule::RevealAll => WinRule::RevealAll,
+            WasmWinRule::RevealOrFlagAll => WinRule::RevealOrFlagAll,
+        }
+    }
 }
@@ -1,3 +1,5 @@
+#![cfg(target_arch = "wasm32")]
+
 use futures::prelude::*;
 use wasm_bindgen::JsValue;
 use wasm_bindgen_futures::JsFuture;
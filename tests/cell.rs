@@ -59,7 +59,7 @@ mod flagging {
             cell,
             Cell {
                 id: 0,
-                state: CellState::Flagged,
+                state: CellState::Flagged(1),
                 ctype: CellType::Empty(0),
                 position: CellPosition { x: 0, y: 0 },
             }
@@ -119,3 +119,89 @@ mod flagging {
         assert!(!is_flagged);
     }
 }
+
+mod multi_mine {
+    use minesweeper_engine::engine::*;
+
+    #[test]
+    fn should_cycle_through_all_flags() {
+        let mut cell = Cell::new(0, CellType::Mine(2), CellPosition { x: 0, y: 0 });
+
+        assert!(cell.flag_up_to(3));
+        assert!(cell.flag_up_to(3));
+        assert!(cell.flag_up_to(3));
+        assert_eq!(cell.state, CellState::Flagged(3));
+        assert_eq!(cell.state.flags(), 3);
+
+        assert!(!cell.flag_up_to(3));
+        assert_eq!(cell.state, CellState::Hidden);
+    }
+
+    #[test]
+    fn should_cycle_through_negative_flags_after_flags() {
+        let mut cell = Cell::new(0, CellType::NegativeMine(1), CellPosition { x: 0, y: 0 });
+
+        assert!(cell.flag_with_negative_up_to(2));
+        assert!(cell.flag_with_negative_up_to(2));
+        assert!(cell.flag_with_negative_up_to(2));
+        assert_eq!(cell.state, CellState::NegativeFlagged(1));
+        assert_eq!(cell.state.charge(), -1);
+
+        assert!(cell.flag_with_negative_up_to(2));
+        assert_eq!(cell.state, CellState::NegativeFlagged(2));
+
+        assert!(!cell.flag_with_negative_up_to(2));
+        assert_eq!(cell.state, CellState::Hidden);
+    }
+
+    #[test]
+    fn should_subtract_negative_mines() {
+        assert_eq!(CellType::NegativeMine(2).charge(), -2);
+        assert_eq!(CellType::NegativeMine(2).mines(), 2);
+        assert!(CellType::NegativeMine(2).is_mine());
+    }
+
+    #[test]
+    fn should_count_mines_of_the_cell() {
+        assert_eq!(CellType::Mine(3).mines(), 3);
+        assert_eq!(CellType::Empty(3).mines(), 0);
+        assert!(CellType::Mine(3).is_mine());
+    }
+}
+
+mod player_view {
+    use minesweeper_engine::engine::*;
+
+    #[test]
+    fn should_hide_the_type_of_hidden_cell() {
+        let cell = Cell::new(0, CellType::Mine(1), CellPosition { x: 0, y: 0 });
+
+        let player_cell = cell.player_view(false);
+
+        assert_eq!(player_cell.ctype, None);
+        assert_eq!(player_cell.state, CellState::Hidden);
+    }
+
+    #[test]
+    fn should_hide_the_type_of_flagged_cell() {
+        let mut cell = Cell::new(0, CellType::Mine(1), CellPosition { x: 0, y: 0 });
+        cell.flag();
+
+        assert_eq!(cell.player_view(false).ctype, None);
+    }
+
+    #[test]
+    fn should_expose_the_type_of_revealed_cell() {
+        let mut cell = Cell::new(0, CellType::Empty(3), CellPosition { x: 0, y: 0 });
+        cell.reveal();
+
+        assert_eq!(cell.player_view(false).ctype, Some(CellType::Empty(3)));
+    }
+
+    #[test]
+    fn should_expose_the_type_of_hidden_cell_when_the_game_is_over() {
+        let cell = Cell::new(0, CellType::Mine(1), CellPosition { x: 0, y: 0 });
+
+        assert_eq!(cell.player_view(true).ctype, Some(CellType::Mine(1)));
+    }
+}
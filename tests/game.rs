@@ -0,0 +1,857 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use minesweeper_engine::engine::{
+    CellId, CellState, Game, GameConfig, GameEvent, GameStatus, Tiling,
+};
+
+/// Subscribes to the game and collects all events it emits
+fn collect_events(game: &mut Game) -> Rc<RefCell<Vec<GameEvent>>> {
+    let events = Rc::new(RefCell::new(vec![]));
+    let listener_events = Rc::clone(&events);
+
+    game.subscribe(move |event| listener_events.borrow_mut().push(*event));
+
+    events
+}
+
+/// Returns ids of all cells which contain mines
+fn mines(game: &Game) -> Vec<CellId> {
+    game.battlefield()
+        .get_all()
+        .iter()
+        .flatten()
+        .filter(|cell| cell.ctype.is_mine())
+        .map(|cell| cell.id)
+        .collect()
+}
+
+/// Returns ids of all existing cells without mines,
+///  the game is won when all of them are revealed
+fn safe_cells(game: &Game) -> Vec<CellId> {
+    game.battlefield()
+        .get_all()
+        .iter()
+        .flatten()
+        .filter(|cell| !cell.ctype.is_mine() && cell.state != CellState::Void)
+        .map(|cell| cell.id)
+        .collect()
+}
+
+#[test]
+fn should_start_the_game_with_all_flags() {
+    let game = Game::new(10, 10, 10);
+    let game_state = game.state();
+
+    assert_eq!(game_state.status, GameStatus::Played);
+    assert_eq!(game_state.flags, 10);
+}
+
+#[test]
+fn should_win_the_game_when_all_empty_cells_are_revealed() {
+    let mut game = Game::with_seed(5, 5, 3, 7);
+
+    let safe_cells = safe_cells(&game);
+
+    for cell_id in safe_cells {
+        game.reveal(cell_id);
+    }
+
+    assert_eq!(game.state().status, GameStatus::Won);
+}
+
+#[test]
+fn should_lose_the_game_when_the_bomb_is_revealed() {
+    let mut game = Game::with_seed(5, 5, 3, 7);
+
+    let bomb = mines(&game)[0];
+
+    let reveal = game.reveal(bomb);
+
+    assert!(reveal.game_is_over);
+    assert_eq!(game.state().status, GameStatus::Lose);
+}
+
+#[test]
+fn should_ignore_actions_when_the_game_has_ended() {
+    let mut game = Game::with_seed(5, 5, 3, 7);
+
+    let bomb = mines(&game)[0];
+
+    game.reveal(bomb);
+
+    let cell = game.flag(bomb);
+    let reveal = game.reveal(bomb);
+
+    assert_eq!(cell.state, CellState::Exploded);
+    assert!(reveal.cells.is_empty());
+    assert_eq!(game.state().status, GameStatus::Lose);
+}
+
+#[test]
+fn should_expose_only_revealed_cells_in_player_view() {
+    let mut game = Game::with_seed(5, 5, 3, 7);
+
+    assert!(game
+        .player_view()
+        .iter()
+        .flatten()
+        .all(|cell| cell.ctype.is_none()));
+
+    let safe_cell = safe_cells(&game)[0];
+
+    game.reveal(safe_cell);
+
+    for cell in game.player_view().iter().flatten() {
+        assert_eq!(cell.ctype.is_some(), cell.state == CellState::Revealed);
+    }
+}
+
+#[test]
+fn should_expose_all_cells_in_player_view_when_the_game_is_won() {
+    let mut game = Game::with_seed(5, 5, 3, 7);
+
+    let safe_cells = safe_cells(&game);
+
+    for cell_id in safe_cells {
+        game.reveal(cell_id);
+    }
+
+    assert!(game
+        .player_view()
+        .iter()
+        .flatten()
+        .all(|cell| cell.ctype.is_some()));
+}
+
+mod events {
+    use super::*;
+
+    #[test]
+    fn should_emit_timer_and_revealed_cells_on_the_first_reveal() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+        let events = collect_events(&mut game);
+
+        let safe_cell = safe_cells(&game)[0];
+
+        let reveal = game.reveal(safe_cell);
+        let events = events.borrow();
+
+        assert_eq!(events[0], GameEvent::TimerStarted);
+        assert_eq!(events.len(), reveal.cells.len() + 1);
+        assert!(events[1..]
+            .iter()
+            .all(|event| matches!(event, GameEvent::CellRevealed(_))));
+    }
+
+    #[test]
+    fn should_emit_detonated_mine_and_lost_game() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+        let events = collect_events(&mut game);
+
+        let bomb = mines(&game)[0];
+
+        game.reveal(bomb);
+        let events = events.borrow();
+        let last_events = &events[events.len() - 2..];
+
+        assert_eq!(
+            last_events[0],
+            GameEvent::MineDetonated(*game.battlefield().get(bomb))
+        );
+        assert_eq!(last_events[1], GameEvent::GameLost);
+    }
+
+    #[test]
+    fn should_emit_won_game() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+        let events = collect_events(&mut game);
+
+        let safe_cells = safe_cells(&game);
+
+        for cell_id in safe_cells {
+            game.reveal(cell_id);
+        }
+
+        assert_eq!(events.borrow().last(), Some(&GameEvent::GameWon));
+    }
+
+    #[test]
+    fn should_emit_flagged_and_unflagged_cells() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+        let events = collect_events(&mut game);
+
+        let flagged = game.flag(0);
+        let unflagged = game.flag(0);
+
+        assert_eq!(flagged.state, CellState::Flagged(1));
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                GameEvent::CellFlagged(flagged),
+                GameEvent::CellUnflagged(unflagged)
+            ]
+        );
+    }
+
+    #[test]
+    fn should_deliver_events_to_every_listener_until_unsubscribed() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+        let first_events = collect_events(&mut game);
+
+        let second_events = Rc::new(RefCell::new(vec![]));
+        let listener_events = Rc::clone(&second_events);
+        let second_id = game.subscribe(move |event| listener_events.borrow_mut().push(*event));
+
+        game.flag(0);
+
+        assert!(game.unsubscribe(second_id));
+        assert!(!game.unsubscribe(second_id));
+
+        game.flag(0);
+
+        assert_eq!(first_events.borrow().len(), 2);
+        assert_eq!(second_events.borrow().len(), 1);
+    }
+}
+
+mod restart {
+    use super::*;
+
+    /// Reveals the first bomb of the battlefield to lose the game
+    fn lose(game: &mut Game) {
+        let bomb = mines(game)[0];
+
+        game.reveal(bomb);
+    }
+
+    #[test]
+    fn should_restart_the_same_map() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+        let initial_map = game.battlefield().get_all().clone();
+
+        game.flag(0);
+        lose(&mut game);
+        game.restart();
+
+        assert_eq!(game.battlefield().get_all(), &initial_map);
+        assert_eq!(game.state().status, GameStatus::Played);
+        assert_eq!(game.state().flags, 3);
+    }
+
+    #[test]
+    fn should_start_a_new_game_by_config() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+
+        lose(&mut game);
+        game.new_game(&GameConfig::new(4, 6, 5).with_seed(7));
+
+        assert_eq!(game.battlefield().rows(), 4);
+        assert_eq!(game.battlefield().cols(), 6);
+        assert_eq!(game.state().flags, 5);
+        assert_eq!(
+            game.battlefield().get_all(),
+            Game::with_seed(4, 6, 5, 7).battlefield().get_all()
+        );
+    }
+
+    #[test]
+    fn should_rematch_on_the_same_kind_of_battlefield() {
+        let config = GameConfig::new(4, 4, 5)
+            .with_layers(2)
+            .with_tiling(Tiling::Hex)
+            .with_wrap(true);
+        let mut game = Game::from_config(&config);
+
+        game.rematch(3);
+
+        assert_eq!(game.battlefield().config(), config);
+        assert_eq!(
+            game.battlefield().get_all(),
+            Game::from_config(&config.with_seed(3))
+                .battlefield()
+                .get_all()
+        );
+    }
+
+    #[test]
+    fn should_rematch_with_the_same_size() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+
+        lose(&mut game);
+        game.rematch(8);
+
+        assert_eq!(game.state().status, GameStatus::Played);
+        assert_eq!(
+            game.battlefield().get_all(),
+            Game::with_seed(5, 5, 3, 8).battlefield().get_all()
+        );
+    }
+
+    #[test]
+    fn should_keep_listeners_and_count_reveals_from_scratch() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+        let events = collect_events(&mut game);
+
+        lose(&mut game);
+        game.restart();
+
+        assert_eq!(events.borrow().last(), Some(&GameEvent::GameReset));
+
+        let safe_cells = safe_cells(&game);
+
+        for cell_id in safe_cells {
+            game.reveal(cell_id);
+        }
+
+        assert_eq!(game.state().status, GameStatus::Won);
+        assert_eq!(events.borrow().last(), Some(&GameEvent::GameWon));
+    }
+}
+
+mod mask {
+    use minesweeper_engine::engine::Mask;
+
+    use super::*;
+
+    #[test]
+    fn should_win_without_revealing_void_cells() {
+        let mask = Mask::from_ascii("###.\n#..#\n####").unwrap();
+        let mut game = Game::from_config(&GameConfig::new(0, 0, 2).with_seed(6).with_mask(mask));
+
+        let safe_cells = safe_cells(&game);
+
+        for cell_id in safe_cells {
+            game.reveal(cell_id);
+        }
+
+        assert_eq!(game.state().status, GameStatus::Won);
+    }
+
+    #[test]
+    fn should_report_void_cells_in_player_view() {
+        let mask = Mask::from_ascii("#.\n##").unwrap();
+        let game = Game::from_config(&GameConfig::new(0, 0, 1).with_mask(mask));
+
+        let player_view = game.player_view();
+
+        assert_eq!(player_view[1][0].state, CellState::Void);
+        assert_eq!(player_view[0][0].state, CellState::Hidden);
+    }
+}
+
+mod multi_mine {
+    use super::*;
+
+    #[test]
+    fn should_win_when_all_cells_without_mines_are_revealed() {
+        let config = GameConfig::new(4, 4, 10)
+            .with_seed(3)
+            .with_max_mines_per_cell(3);
+        let mut game = Game::from_config(&config);
+
+        let safe_cells = safe_cells(&game);
+
+        assert!(game.battlefield().mined_cells_count() < 10);
+
+        for cell_id in safe_cells {
+            game.reveal(cell_id);
+        }
+
+        assert_eq!(game.state().status, GameStatus::Won);
+    }
+
+    #[test]
+    fn should_report_every_added_flag() {
+        let config = GameConfig::new(3, 3, 2).with_max_mines_per_cell(2);
+        let mut game = Game::from_config(&config);
+        let events = collect_events(&mut game);
+
+        game.flag(0);
+        game.flag(0);
+        game.flag(0);
+
+        let states = events
+            .borrow()
+            .iter()
+            .map(|event| match event {
+                GameEvent::CellFlagged(cell) => (true, cell.state),
+                GameEvent::CellUnflagged(cell) => (false, cell.state),
+                _ => panic!("Unexpected event {:?}", event),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            states,
+            vec![
+                (true, CellState::Flagged(1)),
+                (true, CellState::Flagged(2)),
+                (false, CellState::Hidden),
+            ]
+        );
+        assert_eq!(game.state().flags, 2);
+    }
+}
+
+mod negative_mines {
+    use super::*;
+
+    #[test]
+    fn should_win_when_all_cells_without_mines_are_revealed() {
+        let config = GameConfig::new(6, 6, 5).with_seed(9).with_negative_bombs(4);
+        let mut game = Game::from_config(&config);
+
+        assert_eq!(game.state().flags, 9);
+
+        let safe_cells = safe_cells(&game);
+
+        for cell_id in safe_cells {
+            game.reveal(cell_id);
+        }
+
+        assert_eq!(game.state().status, GameStatus::Won);
+    }
+
+    #[test]
+    fn should_report_switching_to_negative_flag() {
+        let config = GameConfig::new(3, 3, 1).with_negative_bombs(1);
+        let mut game = Game::from_config(&config);
+        let events = collect_events(&mut game);
+
+        game.flag(0);
+        let negative_flag = game.flag(0);
+
+        assert_eq!(negative_flag.state, CellState::NegativeFlagged(1));
+        assert_eq!(
+            events.borrow().last(),
+            Some(&GameEvent::CellFlagged(negative_flag))
+        );
+    }
+}
+
+mod lives {
+    use super::*;
+
+    #[test]
+    fn should_keep_playing_until_the_last_life_is_taken() {
+        let config = GameConfig::new(5, 5, 3).with_seed(7).with_lives(3);
+        let mut game = Game::from_config(&config);
+        let events = collect_events(&mut game);
+
+        assert_eq!(game.state().lives, 3);
+
+        let bombs = mines(&game);
+
+        game.reveal(bombs[0]);
+
+        assert_eq!(game.state().status, GameStatus::Played);
+        assert_eq!(game.state().lives, 2);
+        assert_eq!(game.state().flags, 2);
+        assert_eq!(
+            events.borrow().last(),
+            Some(&GameEvent::MineDetonated(*game.battlefield().get(bombs[0])))
+        );
+
+        game.reveal(bombs[1]);
+        game.reveal(bombs[2]);
+
+        assert_eq!(game.state().status, GameStatus::Lose);
+        assert_eq!(game.state().lives, 0);
+    }
+
+    #[test]
+    fn should_win_with_exploded_mines() {
+        let config = GameConfig::new(5, 5, 3).with_seed(7).with_lives(2);
+        let mut game = Game::from_config(&config);
+
+        let bomb = mines(&game)[0];
+        let safe_cells = safe_cells(&game);
+
+        game.reveal(bomb);
+
+        for cell_id in safe_cells {
+            game.reveal(cell_id);
+        }
+
+        assert_eq!(game.state().status, GameStatus::Won);
+        assert_eq!(game.state().lives, 1);
+    }
+
+    #[test]
+    fn should_restore_lives_on_restart() {
+        let config = GameConfig::new(5, 5, 3).with_seed(7).with_lives(2);
+        let mut game = Game::from_config(&config);
+
+        let bomb = mines(&game)[0];
+
+        game.reveal(bomb);
+        game.restart();
+
+        assert_eq!(game.state().lives, 2);
+
+        game.rematch(1);
+
+        assert_eq!(game.state().lives, 2);
+    }
+}
+
+mod loss_report {
+    use super::*;
+
+    #[test]
+    fn should_keep_the_loss_report_until_the_next_game() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+
+        let bombs = mines(&game);
+
+        game.flag(bombs[1]);
+        let reveal = game.reveal(bombs[0]);
+        let report = game.loss_report().unwrap();
+
+        assert_eq!(reveal.loss.as_ref(), Some(report));
+        assert_eq!(report.detonated.id, bombs[0]);
+        assert_eq!(report.correct_flags.len(), 1);
+        assert_eq!(report.unflagged_mines.len(), 1);
+        assert!(report.wrong_flags.is_empty());
+
+        game.restart();
+
+        assert_eq!(game.loss_report(), None);
+    }
+
+    #[test]
+    fn should_show_wrong_flags_in_player_view() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+
+        let safe_cell = safe_cells(&game)[0];
+        let bomb = mines(&game)[0];
+
+        game.flag(safe_cell);
+        game.reveal(bomb);
+
+        let player_view = game.player_view();
+        let cells = player_view.iter().flatten().collect::<Vec<_>>();
+
+        assert_eq!(cells[safe_cell as usize].state, CellState::WrongFlag);
+        assert_eq!(cells[bomb as usize].state, CellState::Exploded);
+    }
+}
+
+mod win {
+    use super::*;
+
+    #[test]
+    fn should_flag_all_remaining_mines_on_win() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+        let events = collect_events(&mut game);
+
+        let bombs = mines(&game);
+        let safe_cells = safe_cells(&game);
+
+        game.flag(bombs[0]);
+
+        let mut last_reveal = None;
+        for cell_id in safe_cells {
+            let reveal = game.reveal(cell_id);
+
+            if !reveal.cells.is_empty() {
+                last_reveal = Some(reveal);
+            }
+        }
+
+        let flagged = last_reveal
+            .unwrap()
+            .flagged
+            .iter()
+            .map(|cell| cell.id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(flagged, bombs[1..].to_vec());
+        assert_eq!(game.state().flags, 0);
+        assert!(bombs
+            .iter()
+            .all(|bomb| game.battlefield().get(*bomb).state == CellState::Flagged(1)));
+
+        let events = events.borrow();
+        let last_events = &events[events.len() - 3..];
+
+        assert!(matches!(last_events[0], GameEvent::CellFlagged(_)));
+        assert!(matches!(last_events[1], GameEvent::CellFlagged(_)));
+        assert_eq!(last_events[2], GameEvent::GameWon);
+    }
+}
+
+mod win_by_flags {
+    use minesweeper_engine::engine::WinRule;
+
+    use super::*;
+
+    #[test]
+    fn should_win_when_all_mines_are_flagged() {
+        let config = GameConfig::new(5, 5, 3)
+            .with_seed(7)
+            .with_win_rule(WinRule::RevealOrFlagAll);
+        let mut game = Game::from_config(&config);
+        let events = collect_events(&mut game);
+        for mine in mines(&game).iter() {
+            assert!(game.is_played());
+
+            game.flag(*mine);
+        }
+
+        assert_eq!(game.state().status, GameStatus::Won);
+        assert_eq!(game.state().flags, 0);
+        assert_eq!(events.borrow().last(), Some(&GameEvent::GameWon));
+    }
+
+    #[test]
+    fn should_not_win_by_flags_on_the_board_without_mines() {
+        let config = GameConfig::new(3, 3, 0).with_win_rule(WinRule::RevealOrFlagAll);
+        let mut game = Game::from_config(&config);
+
+        game.flag(0);
+        game.flag(0);
+
+        assert!(game.is_played());
+
+        for cell_id in safe_cells(&game) {
+            game.reveal(cell_id);
+        }
+
+        assert_eq!(game.state().status, GameStatus::Won);
+    }
+
+    #[test]
+    fn should_not_win_by_flags_while_wrong_flags_exist() {
+        let config = GameConfig::new(5, 5, 3)
+            .with_seed(7)
+            .with_win_rule(WinRule::RevealOrFlagAll);
+        let mut game = Game::from_config(&config);
+        let (mines, empty) = (mines(&game), safe_cells(&game)[0]);
+
+        game.flag(empty);
+        for mine in mines[..2].iter() {
+            game.flag(*mine);
+        }
+
+        // The last flag is taken by the wrong one
+        game.flag(mines[2]);
+
+        assert!(game.is_played());
+
+        game.flag(empty);
+        game.flag(mines[2]);
+
+        assert_eq!(game.state().status, GameStatus::Won);
+    }
+
+    #[test]
+    fn should_not_win_by_flags_with_default_rule() {
+        let mut game = Game::with_seed(5, 5, 3, 7);
+        for mine in mines(&game).iter() {
+            game.flag(*mine);
+        }
+
+        assert!(game.is_played());
+    }
+
+    #[test]
+    fn should_keep_win_rule_on_rematch() {
+        let config = GameConfig::new(5, 5, 3).with_win_rule(WinRule::RevealOrFlagAll);
+        let mut game = Game::from_config(&config);
+
+        game.rematch(3);
+
+        assert_eq!(game.win_rule(), WinRule::RevealOrFlagAll);
+    }
+}
+
+mod kind_mode {
+    use minesweeper_engine::engine::{BoardEditor, CellPosition, GuessMode, Solver};
+
+    use super::*;
+
+    #[test]
+    fn should_move_the_mine_away_from_the_first_guess() {
+        let config = GameConfig::new(6, 6, 8)
+            .with_seed(3)
+            .with_guess_mode(GuessMode::Kind);
+        let mut game = Game::from_config(&config);
+        let mine = mines(&game)[0];
+
+        game.reveal(mine);
+
+        assert!(game.is_played());
+        assert_eq!(game.battlefield().get(mine).state, CellState::Revealed);
+        assert_eq!(game.battlefield().mined_cells_count(), 8);
+    }
+
+    /// Builds the single row of 5 cells with mines in `mines` columns
+    ///  and reveals the middle cell which sees only the mine at `1`
+    fn row_game(mines: &[i16]) -> Game {
+        let mut editor = BoardEditor::new(&GameConfig::new(1, 5, 0));
+
+        for x in mines {
+            editor.toggle_mine(CellPosition { x: *x, y: 0 }).unwrap();
+        }
+
+        let mut game = Game::from_battlefield(editor.into_battlefield());
+        game.set_guess_mode(GuessMode::Kind).unwrap();
+        game.reveal(2);
+
+        game
+    }
+
+    #[test]
+    fn should_keep_mines_when_a_safe_cell_is_known() {
+        // |?, b, 1, ?, ?| with the only mine, so both edges are safe
+        //  by the count of mines though no clue tells it
+        let mut game = row_game(&[1]);
+
+        assert!(Solver::new(game.battlefield()).deduce().safe.is_empty());
+
+        game.reveal(1);
+
+        assert_eq!(game.state().status, GameStatus::Lose);
+    }
+
+    #[test]
+    fn should_move_the_mine_when_nothing_is_proved_safe() {
+        // |?, b, 1, ?, b| where every hidden cell may contain a mine
+        let mut game = row_game(&[1, 4]);
+
+        game.reveal(1);
+
+        // Both mines are at the right edge now, so nothing is left to reveal
+        assert_eq!(mines(&game), vec![3, 4]);
+        assert_eq!(game.state().status, GameStatus::Won);
+    }
+
+    #[test]
+    fn should_reject_boards_with_several_or_negative_mines_per_cell() {
+        let config = GameConfig::new(5, 5, 3).with_guess_mode(GuessMode::Kind);
+
+        assert!(config.validate().is_ok());
+        assert!(config
+            .clone()
+            .with_max_mines_per_cell(3)
+            .validate()
+            .is_err());
+        assert!(config.with_negative_bombs(2).validate().is_err());
+
+        let config = GameConfig::new(5, 5, 3).with_max_mines_per_cell(3);
+        let mut game = Game::from_config(&config);
+
+        assert!(game.set_guess_mode(GuessMode::Kind).is_err());
+        assert_eq!(game.guess_mode(), GuessMode::Normal);
+    }
+
+    #[test]
+    fn should_keep_guess_mode_on_rematch() {
+        let config = GameConfig::new(5, 5, 3).with_guess_mode(GuessMode::Kind);
+        let mut game = Game::from_config(&config);
+
+        game.rematch(3);
+
+        assert_eq!(game.guess_mode(), GuessMode::Kind);
+    }
+}
+
+mod cruel_mode {
+    use minesweeper_engine::engine::{CellType, GuessMode, Solver};
+
+    use super::*;
+
+    fn cruel_game(seed: u64) -> Game {
+        let config = GameConfig::new(8, 8, 10)
+            .with_seed(seed)
+            .with_guess_mode(GuessMode::Cruel);
+
+        Game::from_config(&config)
+    }
+
+    fn opening(game: &Game) -> u16 {
+        game.battlefield()
+            .get_all()
+            .iter()
+            .flatten()
+            .find(|cell| cell.ctype == CellType::Empty(0))
+            .unwrap()
+            .id
+    }
+
+    #[test]
+    fn should_not_touch_the_first_reveal() {
+        let mut game = cruel_game(1);
+        let opening = opening(&game);
+
+        game.reveal(opening);
+
+        assert!(game.is_played());
+        assert_eq!(game.battlefield().get(opening).state, CellState::Revealed);
+    }
+
+    #[test]
+    fn should_put_the_mine_into_the_guessed_cell() {
+        let mut game = cruel_game(1);
+        game.reveal(opening(&game));
+
+        // The cell far from revealed ones may contain a mine
+        let guess = game
+            .battlefield()
+            .get_all()
+            .iter()
+            .flatten()
+            .find(|cell| {
+                cell.state == CellState::Hidden
+                    && !cell.ctype.is_mine()
+                    && game
+                        .battlefield()
+                        .neighbours(cell.position)
+                        .iter()
+                        .all(|neighbour| neighbour.state == CellState::Hidden)
+            })
+            .unwrap()
+            .id;
+
+        game.reveal(guess);
+
+        assert_eq!(game.state().status, GameStatus::Lose);
+        assert_eq!(game.battlefield().mined_cells_count(), 10);
+    }
+
+    #[test]
+    fn should_keep_cells_which_are_proved_safe() {
+        let mut game = cruel_game(2);
+        game.reveal(opening(&game));
+
+        let safe = Solver::new(game.battlefield()).deduce().safe;
+
+        assert!(!safe.is_empty());
+
+        for cell_id in safe {
+            game.reveal(cell_id);
+
+            assert_ne!(game.state().status, GameStatus::Lose);
+        }
+    }
+
+    #[test]
+    fn should_reject_boards_with_several_or_negative_mines_per_cell() {
+        let config = GameConfig::new(8, 8, 10).with_guess_mode(GuessMode::Cruel);
+
+        assert!(config.validate().is_ok());
+        assert!(config
+            .clone()
+            .with_max_mines_per_cell(2)
+            .validate()
+            .is_err());
+        assert!(config.with_negative_bombs(3).validate().is_err());
+
+        let config = GameConfig::new(8, 8, 10).with_negative_bombs(3);
+        let mut game = Game::from_config(&config);
+
+        assert!(game.set_guess_mode(GuessMode::Cruel).is_err());
+        assert!(game.set_guess_mode(GuessMode::Normal).is_ok());
+    }
+}
@@ -1,9 +1,112 @@
-use minesweeper_engine::MineSweeperEngine;
+#![cfg(feature = "wasm")]
+
+use minesweeper_engine::{EditorEngine, GameConfig, MineSweeperEngine, WasmTopology};
 
 #[test]
 fn should_return_initial_flags_value() {
-    let engine = MineSweeperEngine::create(10, 10, 10);
+    let engine = MineSweeperEngine::create(10, 10, 10).unwrap();
     let game_state = engine.game_state();
 
     assert_eq!(game_state.flags, 10);
 }
+
+#[test]
+fn should_create_the_board_with_more_bombs_than_cells() {
+    let engine = MineSweeperEngine::create(3, 3, 10).unwrap();
+
+    assert_eq!(engine.game_state().flags, 9);
+}
+
+#[test]
+fn should_keep_the_size_on_rematch() {
+    let mut config = GameConfig::new(8, 6, 5);
+    config.topology = WasmTopology::Hex;
+    config.wrap = true;
+    config.set_neighbourhood("von-neumann").unwrap();
+
+    let mut engine = MineSweeperEngine::create_with_config(config).unwrap();
+    engine.rematch(42);
+
+    assert_eq!(engine.rows(), 8);
+    assert_eq!(engine.cols(), 6);
+    assert_eq!(engine.game_state().flags, 5);
+    assert_eq!(engine.topology(), WasmTopology::Hex);
+    assert!(engine.is_wrapped());
+    assert_eq!(engine.neighbourhood(), "von-neumann");
+}
+
+#[test]
+fn should_record_the_neighbourhood_of_the_config() {
+    let mut config = GameConfig::new(6, 6, 4);
+    config.set_neighbourhood("custom:1,0;0,-2").unwrap();
+
+    let engine = MineSweeperEngine::create_with_config(config).unwrap();
+
+    assert_eq!(engine.neighbourhood(), "custom:1,0;0,-2");
+}
+
+#[test]
+fn should_create_3d_battlefield_by_config() {
+    let mut config = GameConfig::new(4, 5, 6);
+    config.layers = 3;
+
+    let engine = MineSweeperEngine::create_with_config(config).unwrap();
+
+    assert_eq!(engine.rows(), 4);
+    assert_eq!(engine.cols(), 5);
+    assert_eq!(engine.layers(), 3);
+}
+
+#[test]
+fn should_take_the_size_from_the_mask() {
+    let mut config = GameConfig::new(10, 10, 2);
+    config.set_mask(".#.\n###\n.#.").unwrap();
+
+    let engine = MineSweeperEngine::create_with_config(config).unwrap();
+
+    assert_eq!(engine.rows(), 3);
+    assert_eq!(engine.cols(), 3);
+}
+
+#[test]
+fn should_start_with_all_lives_of_the_config() {
+    let mut config = GameConfig::new(6, 6, 4);
+    config.lives = 3;
+
+    let engine = MineSweeperEngine::create_with_config(config).unwrap();
+
+    assert_eq!(engine.game_state().lives, 3);
+}
+
+#[test]
+fn should_play_the_board_built_by_editor() {
+    let mut editor = EditorEngine::new(GameConfig::new(5, 5, 10)).unwrap();
+
+    assert_eq!(editor.bombs(), 0);
+
+    editor.randomize_region(0, 0, 1, 1, 3, Some(7)).unwrap();
+
+    assert_eq!(editor.bombs(), 3);
+    assert!(editor.mines().iter().all(|id| [0, 1, 5, 6].contains(id)));
+
+    let engine = editor.play();
+
+    assert_eq!(engine.game_state().flags, 3);
+
+    editor.clear();
+
+    assert!(editor.mines().is_empty());
+    assert_eq!(engine.game_state().flags, 3);
+}
+
+#[test]
+fn should_build_the_board_again_from_mines_of_editor() {
+    let mut editor = EditorEngine::new(GameConfig::new(5, 5, 0)).unwrap();
+    editor.randomize_region(0, 0, 4, 4, 6, Some(3)).unwrap();
+
+    let mut rebuilt = EditorEngine::new(GameConfig::new(5, 5, 0)).unwrap();
+    rebuilt.set_mines(editor.mines()).unwrap();
+
+    assert_eq!(rebuilt.mines(), editor.mines());
+    assert_eq!(rebuilt.bombs(), 6);
+}
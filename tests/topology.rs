@@ -0,0 +1,134 @@
+use minesweeper_engine::engine::{
+    BattleField, CellPosition, CellState, CellType, Game, GameStatus, Solver, Topology,
+};
+
+/// Cells placed in a circle where every cell
+///  has neighbours on the left and on the right
+struct Ring {
+    cells: usize,
+}
+
+impl Topology for Ring {
+    fn width(&self) -> usize {
+        self.cells
+    }
+
+    fn height(&self) -> usize {
+        1
+    }
+
+    fn neighbours(&self, position: CellPosition) -> Vec<CellPosition> {
+        let cells = self.cells as i16;
+
+        vec![
+            CellPosition {
+                x: (position.x + cells - 1) % cells,
+                y: 0,
+            },
+            CellPosition {
+                x: (position.x + 1) % cells,
+                y: 0,
+            },
+        ]
+    }
+}
+
+/// Every second cell of the ring exists
+struct DottedRing;
+
+impl Topology for DottedRing {
+    fn width(&self) -> usize {
+        8
+    }
+
+    fn height(&self) -> usize {
+        1
+    }
+
+    fn contains(&self, position: CellPosition) -> bool {
+        position.x % 2 == 0
+    }
+
+    fn neighbours(&self, position: CellPosition) -> Vec<CellPosition> {
+        Ring { cells: 8 }.neighbours(position)
+    }
+}
+
+#[test]
+fn should_count_mines_by_custom_topology() {
+    let battlefield = BattleField::from_topology_with_seed(Ring { cells: 12 }, 4, 1);
+
+    for cell in battlefield.get_all().iter().flatten() {
+        assert_eq!(battlefield.neighbours(cell.position).len(), 2);
+
+        if let CellType::Empty(value) = cell.ctype {
+            let mines = battlefield
+                .neighbours(cell.position)
+                .iter()
+                .filter(|neighbour| neighbour.ctype.is_mine())
+                .count();
+
+            assert_eq!(value as usize, mines);
+        }
+    }
+}
+
+#[test]
+fn should_solve_the_battlefield_of_custom_topology() {
+    let mut battlefield = BattleField::from_topology_with_seed(Ring { cells: 12 }, 3, 5);
+
+    let opening = battlefield
+        .get_all()
+        .iter()
+        .flatten()
+        .find(|cell| cell.ctype == CellType::Empty(0))
+        .map(|cell| cell.id)
+        .unwrap();
+    battlefield.reveal(opening);
+
+    let deduction = Solver::new(&battlefield).deduce();
+
+    assert!(!deduction.mines.is_empty());
+    for cell_id in deduction.safe {
+        assert_ne!(battlefield.get(cell_id).ctype, CellType::Mine(1));
+    }
+    for cell_id in deduction.mines {
+        assert_eq!(battlefield.get(cell_id).ctype, CellType::Mine(1));
+    }
+}
+
+#[test]
+fn should_win_the_game_on_custom_topology() {
+    let battlefield = BattleField::from_topology_with_seed(Ring { cells: 12 }, 3, 5);
+    let mut game = Game::from_battlefield(battlefield);
+
+    let safe_cells = game
+        .battlefield()
+        .get_all()
+        .iter()
+        .flatten()
+        .filter(|cell| !cell.ctype.is_mine())
+        .map(|cell| cell.id)
+        .collect::<Vec<_>>();
+
+    for cell_id in safe_cells {
+        game.reveal(cell_id);
+    }
+
+    assert_eq!(game.state().status, GameStatus::Won);
+}
+
+#[test]
+fn should_skip_cells_which_do_not_exist() {
+    let battlefield = BattleField::from_topology(DottedRing, 2);
+
+    assert_eq!(battlefield.void_cells_count(), 4);
+
+    for cell in battlefield.get_all().iter().flatten() {
+        if cell.state == CellState::Void {
+            assert_ne!(cell.ctype, CellType::Mine(1));
+        } else {
+            assert!(battlefield.neighbours(cell.position).is_empty());
+        }
+    }
+}